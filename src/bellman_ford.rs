@@ -1,8 +1,5 @@
-use crate::graph::{GraphList, Node};
-use core::f64;
+use crate::graph::GraphList;
 use ordered_float::OrderedFloat;
-use std::cmp::{self, Ordering, Reverse};
-use std::collections::BinaryHeap;
 
 pub fn bellman_ford(g: &GraphList, start: usize) -> Option<Vec<OrderedFloat<f64>>> {
     let mut cost = vec![OrderedFloat(f64::INFINITY); g.num_nodes()];
@@ -23,11 +20,12 @@ pub fn bellman_ford(g: &GraphList, start: usize) -> Option<Vec<OrderedFloat<f64>
             return None;
         }
     }
-    return Some(cost);
+    Some(cost)
 }
 #[cfg(test)]
 mod bellman_ford_tests {
     use super::*;
+    use crate::graph::Node;
     use ordered_float::OrderedFloat;
 
     // Helper function to create a basic test graph
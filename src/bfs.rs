@@ -1,4 +1,5 @@
-use crate::graph::{GraphList, Node};
+use crate::graph::GraphList;
+use crate::visitor::Visitor;
 use std::collections::VecDeque;
 
 pub fn bfs(g: &GraphList, start: usize) -> Vec<isize> {
@@ -8,11 +9,9 @@ pub fn bfs(g: &GraphList, start: usize) -> Vec<isize> {
     pending.push_back(start);
     seen[start] = true;
     let all_edges: Vec<_> = (0..g.num_nodes())
-        .into_iter()
         .map(|node_indx| g.nodes[node_indx].get_ordered_edge_list())
         .collect();
-    while !pending.is_empty() {
-        let next = pending.pop_front().unwrap();
+    while let Some(next) = pending.pop_front() {
         for e in &all_edges[next] {
             let neighbour = e.to;
             if !seen[neighbour] {
@@ -22,13 +21,144 @@ pub fn bfs(g: &GraphList, start: usize) -> Vec<isize> {
             }
         }
     }
-    return last;
+    last
+}
+
+/// The result of a BFS from one or more sources. Nodes not reached have
+/// `None` everywhere.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BfsTree {
+    /// Number of edges from the nearest source.
+    pub distance: Vec<Option<usize>>,
+    /// The source a node was reached from, which is one of its nearest.
+    pub nearest: Vec<Option<usize>>,
+    /// None for the sources too.
+    pub parent: Vec<Option<usize>>,
+}
+
+impl BfsTree {
+    /// Reached nodes grouped by distance, each level in increasing order.
+    pub fn levels(&self) -> Vec<Vec<usize>> {
+        let mut levels = vec![];
+        for (node, distance) in self.distance.iter().enumerate() {
+            if let Some(d) = *distance {
+                if levels.len() <= d {
+                    levels.resize(d + 1, vec![]);
+                }
+                levels[d].push(node);
+            }
+        }
+        levels
+    }
+}
+
+/// BFS from all of `sources` at once, so every node is reached from its
+/// nearest source. With `max_depth`, nodes further away are not reached.
+pub fn multi_source_bfs(g: &GraphList, sources: &[usize], max_depth: Option<usize>) -> BfsTree {
+    let n = g.num_nodes();
+    let mut tree = BfsTree {
+        distance: vec![None; n],
+        nearest: vec![None; n],
+        parent: vec![None; n],
+    };
+    let mut pending = VecDeque::new();
+    for &source in sources {
+        if tree.distance[source].is_none() {
+            tree.distance[source] = Some(0);
+            tree.nearest[source] = Some(source);
+            pending.push_back(source);
+        }
+    }
+    while let Some(next) = pending.pop_front() {
+        let d = tree.distance[next].unwrap();
+        if max_depth.is_some_and(|max| d >= max) {
+            continue;
+        }
+        for e in g.nodes[next].get_ordered_edge_list() {
+            if tree.distance[e.to].is_none() {
+                tree.distance[e.to] = Some(d + 1);
+                tree.nearest[e.to] = tree.nearest[next];
+                tree.parent[e.to] = Some(next);
+                pending.push_back(e.to);
+            }
+        }
+    }
+    tree
+}
+
+/// Number of edges on a shortest path from `start` to every node.
+pub fn bfs_distances(g: &GraphList, start: usize) -> Vec<Option<usize>> {
+    multi_source_bfs(g, &[start], None).distance
+}
+
+/// Nodes grouped by their distance from the nearest of `sources`, up to
+/// `max_depth`.
+pub fn bfs_levels(g: &GraphList, sources: &[usize], max_depth: Option<usize>) -> Vec<Vec<usize>> {
+    multi_source_bfs(g, sources, max_depth).levels()
+}
+
+/// Nodes reachable from a start node in breadth-first order, computed as
+/// they are asked for.
+pub struct Bfs<'a> {
+    g: &'a GraphList,
+    seen: Vec<bool>,
+    pending: VecDeque<usize>,
+}
+
+impl<'a> Bfs<'a> {
+    pub fn new(g: &'a GraphList, start: usize) -> Self {
+        let mut seen = vec![false; g.num_nodes()];
+        seen[start] = true;
+        Bfs {
+            g,
+            seen,
+            pending: VecDeque::from([start]),
+        }
+    }
+}
+
+impl Iterator for Bfs<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let next = self.pending.pop_front()?;
+        for e in self.g.nodes[next].get_ordered_edge_list() {
+            if !self.seen[e.to] {
+                self.seen[e.to] = true;
+                self.pending.push_back(e.to);
+            }
+        }
+        Some(next)
+    }
+}
+
+/// Breadth-first search from `start`, reporting its events to `visitor`.
+/// Edges are examined in order of their targets.
+pub fn bfs_visit(g: &GraphList, start: usize, visitor: &mut impl Visitor) {
+    let mut seen = vec![false; g.num_nodes()];
+    let mut pending = VecDeque::from([start]);
+    seen[start] = true;
+    visitor.discover(start);
+    while let Some(next) = pending.pop_front() {
+        for e in g.nodes[next].get_ordered_edge_list() {
+            visitor.examine_edge(e);
+            if seen[e.to] {
+                visitor.cross_edge(e);
+            } else {
+                seen[e.to] = true;
+                visitor.tree_edge(e);
+                visitor.discover(e.to);
+                pending.push_back(e.to);
+            }
+        }
+        visitor.finish(next);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::VecDeque;
+    use crate::graph::Node;
 
     // Helper function to create a simple directed graph for testing
     fn create_test_graph() -> GraphList {
@@ -156,4 +286,99 @@ mod tests {
         let result = bfs(&graph, 0);
         assert_eq!(result, vec![-1, 0, 0]);
     }
+
+    #[test]
+    fn test_bfs_iterator() {
+        let graph = create_test_graph();
+        assert_eq!(Bfs::new(&graph, 0).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(Bfs::new(&graph, 2).collect::<Vec<_>>(), vec![2, 3]);
+        // Stopping early leaves the rest of the graph unexplored.
+        assert_eq!(Bfs::new(&graph, 0).find(|&i| i == 1), Some(1));
+    }
+
+    #[derive(Default)]
+    struct Events(Vec<String>);
+
+    impl Visitor for Events {
+        fn discover(&mut self, node: usize) {
+            self.0.push(format!("discover {node}"));
+        }
+        fn tree_edge(&mut self, e: &crate::graph::Edge) {
+            self.0.push(format!("tree {} {}", e.from, e.to));
+        }
+        fn cross_edge(&mut self, e: &crate::graph::Edge) {
+            self.0.push(format!("cross {} {}", e.from, e.to));
+        }
+        fn finish(&mut self, node: usize) {
+            self.0.push(format!("finish {node}"));
+        }
+    }
+
+    #[test]
+    fn test_bfs_visit() {
+        let mut events = Events::default();
+        bfs_visit(&create_test_graph(), 0, &mut events);
+        assert_eq!(
+            events.0,
+            [
+                "discover 0",
+                "tree 0 1",
+                "discover 1",
+                "tree 0 2",
+                "discover 2",
+                "finish 0",
+                "tree 1 3",
+                "discover 3",
+                "finish 1",
+                "cross 2 3",
+                "finish 2",
+                "finish 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bfs_distances() {
+        let graph = create_test_graph();
+        assert_eq!(
+            bfs_distances(&graph, 0),
+            vec![Some(0), Some(1), Some(1), Some(2)]
+        );
+        assert_eq!(bfs_distances(&graph, 2), vec![None, None, Some(0), Some(1)]);
+        assert_eq!(
+            bfs_levels(&graph, &[0], None),
+            vec![vec![0], vec![1, 2], vec![3]]
+        );
+        assert_eq!(bfs_levels(&graph, &[0], Some(1)), vec![vec![0], vec![1, 2]]);
+        assert_eq!(bfs_levels(&graph, &[0], Some(0)), vec![vec![0]]);
+    }
+
+    #[test]
+    fn test_multi_source_bfs() {
+        // A path 0 - 1 - 2 - 3 - 4 - 5 with facilities at 0 and 4.
+        let mut graph = GraphList::with_nodes(true, 6);
+        for i in 0..5 {
+            graph.insert_edge(i, i + 1, 1.0).unwrap();
+        }
+        let tree = multi_source_bfs(&graph, &[4, 0, 4], None);
+        assert_eq!(
+            tree.distance,
+            vec![Some(0), Some(1), Some(2), Some(1), Some(0), Some(1)]
+        );
+        assert_eq!(
+            tree.nearest,
+            vec![Some(0), Some(0), Some(4), Some(4), Some(4), Some(4)]
+        );
+        assert_eq!(
+            tree.parent,
+            vec![None, Some(0), Some(3), Some(4), None, Some(4)]
+        );
+        assert_eq!(tree.levels(), vec![vec![0, 4], vec![1, 3, 5], vec![2]]);
+
+        let tree = multi_source_bfs(&graph, &[0], Some(2));
+        assert_eq!(tree.distance[2], Some(2));
+        assert_eq!(tree.distance[3], None);
+        assert_eq!(tree.nearest[3], None);
+        assert!(multi_source_bfs(&graph, &[], None).levels().is_empty());
+    }
 }
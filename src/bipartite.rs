@@ -0,0 +1,144 @@
+use crate::graph::GraphList;
+use std::collections::VecDeque;
+
+// Bipartiteness by BFS 2-coloring: neighbors get opposite colors, and an
+// edge between two nodes of the same color closes an odd cycle. Edge
+// directions are ignored.
+
+/// The two sides of a bipartite graph, the lowest node of every connected
+/// component on the first, each in increasing order. Otherwise an odd
+/// cycle: nodes in order, each joined to the next and the last to the
+/// first. A self-loop is a cycle of one node.
+pub fn bipartition(g: &GraphList) -> Result<(Vec<usize>, Vec<usize>), Vec<usize>> {
+    let n = g.num_nodes();
+    let mut neighbors = vec![vec![]; n];
+    for e in g.make_edge_list() {
+        neighbors[e.from].push(e.to);
+        neighbors[e.to].push(e.from);
+    }
+    for adjacent in &mut neighbors {
+        adjacent.sort_unstable();
+        adjacent.dedup();
+    }
+
+    let mut depth: Vec<Option<usize>> = vec![None; n];
+    let mut parent = vec![None; n];
+    for start in 0..n {
+        if depth[start].is_some() {
+            continue;
+        }
+        depth[start] = Some(0);
+        let mut pending = VecDeque::from([start]);
+        while let Some(node) = pending.pop_front() {
+            let d = depth[node].unwrap();
+            for &next in &neighbors[node] {
+                match depth[next] {
+                    None => {
+                        depth[next] = Some(d + 1);
+                        parent[next] = Some(node);
+                        pending.push_back(next);
+                    }
+                    Some(other) if other % 2 == d % 2 => {
+                        return Err(odd_cycle(&parent, &depth, node, next));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+    let (even, odd) = (0..n).partition(|&i| depth[i].unwrap().is_multiple_of(2));
+    Ok((even, odd))
+}
+
+// The cycle closed by the edge `a - b` between nodes of the same color: the
+// tree paths from both up to their lowest common ancestor, plus the edge.
+fn odd_cycle(parent: &[Option<usize>], depth: &[Option<usize>], a: usize, b: usize) -> Vec<usize> {
+    let (mut up_a, mut up_b) = (vec![a], vec![b]);
+    let (mut x, mut y) = (a, b);
+    while x != y {
+        if depth[x] >= depth[y] {
+            x = parent[x].unwrap();
+            up_a.push(x);
+        } else {
+            y = parent[y].unwrap();
+            up_b.push(y);
+        }
+    }
+    // Both paths end at the common ancestor; keep it once.
+    up_b.pop();
+    up_a.reverse();
+    up_a.extend(up_b);
+    up_a
+}
+
+pub fn is_bipartite(g: &GraphList) -> bool {
+    bipartition(g).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_cycle(g: &GraphList, cycle: &[usize]) -> bool {
+        let joined = |a: usize, b: usize| g.is_edge(a, b) || g.is_edge(b, a);
+        cycle.len() % 2 == 1
+            && (0..cycle.len()).all(|i| joined(cycle[i], cycle[(i + 1) % cycle.len()]))
+    }
+
+    #[test]
+    fn test_bipartite() {
+        // An even cycle 0 - 1 - 2 - 3, with 4 hanging off 2 and 5, 6 apart.
+        let mut g = GraphList::with_nodes(true, 7);
+        for (a, b) in [(0, 1), (1, 2), (2, 3), (3, 0), (2, 4), (5, 6)] {
+            g.insert_edge(a, b, 1.0).unwrap();
+        }
+        assert_eq!(bipartition(&g), Ok((vec![0, 2, 5], vec![1, 3, 4, 6])));
+        assert!(is_bipartite(&g));
+        assert_eq!(
+            bipartition(&GraphList::with_nodes(true, 0)),
+            Ok((vec![], vec![]))
+        );
+    }
+
+    #[test]
+    fn test_odd_cycles() {
+        let mut g = GraphList::with_nodes(true, 7);
+        for (a, b) in [(0, 1), (1, 2), (2, 3), (3, 0), (5, 6), (6, 4), (4, 3)] {
+            g.insert_edge(a, b, 1.0).unwrap();
+        }
+        assert!(is_bipartite(&g));
+        // 4 - 5 closes the triangle 4 - 5 - 6, both 5 and 6 hanging off 4.
+        g.insert_edge(4, 5, 1.0).unwrap();
+        let cycle = bipartition(&g).unwrap_err();
+        assert_eq!(cycle, vec![4, 5, 6]);
+        assert!(is_cycle(&g, &cycle));
+
+        // A cycle reached only from the second component.
+        let mut g = GraphList::with_nodes(true, 8);
+        g.insert_edge(0, 1, 1.0).unwrap();
+        for (a, b) in [(2, 3), (3, 4), (4, 5), (5, 6), (6, 2)] {
+            g.insert_edge(a, b, 1.0).unwrap();
+        }
+        let cycle = bipartition(&g).unwrap_err();
+        assert_eq!(cycle.len(), 5);
+        assert!(is_cycle(&g, &cycle));
+
+        let mut g = GraphList::with_nodes(false, 2);
+        g.insert_edge(1, 1, 1.0).unwrap();
+        assert_eq!(bipartition(&g), Err(vec![1]));
+    }
+
+    #[test]
+    fn test_directed() {
+        // Directions do not matter: 0 -> 1 <- 2 is a path, and with 0 -> 2 a
+        // triangle.
+        let mut g = GraphList::with_nodes(false, 3);
+        g.insert_edge(0, 1, 1.0).unwrap();
+        g.insert_edge(2, 1, 1.0).unwrap();
+        assert_eq!(bipartition(&g), Ok((vec![0, 2], vec![1])));
+        g.insert_edge(0, 2, 1.0).unwrap();
+        let cycle = bipartition(&g).unwrap_err();
+        assert!(is_cycle(&g, &cycle));
+        assert_eq!(cycle.len(), 3);
+    }
+}
@@ -0,0 +1,903 @@
+use crate::bipartite::bipartition;
+use crate::convert::{
+    drop_weights, largest_component, lossy_conversions, relabel, to_directed, to_undirected,
+    Relabel,
+};
+use crate::dfs::dfs_connected_componentes;
+use crate::formats::file::Format;
+use crate::graph::GraphList;
+use crate::paths::{
+    bellman_ford_tree, bfs_tree, dfs_tree, dijkstra_tree, floyd_warshall_paths, ShortestPathTree,
+};
+use crate::repl::{self, Session};
+use crate::scc::tarjan_scc;
+use crate::server::{self, ServerState};
+use crate::stats::{stats, DegreeStats, GraphStats};
+use crate::topological::topological_sort;
+use ordered_float::OrderedFloat;
+use serde_json::{json, Map, Value};
+use std::fmt::Write;
+use std::io;
+use std::net::TcpListener;
+use std::sync::{Arc, RwLock};
+
+// The `algo3` command line. `run` takes the arguments after the program
+// name and returns what to print, so it can be tested without a process.
+
+pub const USAGE: &str = "\
+Usage: algo3 <command> <graph-file> [options]
+       algo3 convert <graph-file> <output-file> [convert options]
+       algo3 repl [graph-file]
+       algo3 serve <graph-file> [--listen <address>]
+
+Commands:
+  bfs              BFS parent of every node, or the path to --target
+  dfs              DFS parent of every node, or the path to --target
+  components       connected component of every node, weakly if directed
+  scc              strongly connected component of every node
+  topo             nodes in topological order, or a cycle if there is none
+  bipartite        side of every node in a 2-coloring, or an odd cycle
+  dijkstra         distance to every node, or the path to --target
+  bellman-ford     like dijkstra, allowing negative weights
+  floyd-warshall   predecessor matrix, or the path from --source to --target
+  stats            size, density, degrees, components, self-loops, ...
+
+Options:
+  -s, --source <node>     start node, by label or index
+  -t, --target <node>     end node, by label or index
+  -o, --output <format>   table (default), json or csv
+  -h, --help              print this help
+
+Convert options:
+  --to <format>           output format, by default from the output extension
+  --relabel <index|none>  label nodes with their index, or remove labels
+  --directed              make edges into arcs both ways
+  --undirected            make arcs into edges, keeping the smaller weight
+  --drop-weights          set every weight to 1
+  --largest-component     keep only the largest weakly connected component
+
+`repl` starts an interactive session on the graph, or on an empty directed
+graph; type `help` there for its commands.
+
+`serve` answers JSON queries on the graph over HTTP, on 127.0.0.1:7878
+unless --listen says otherwise. See `server.rs` for the endpoints.
+
+The graph file format is detected from its content and extension. Output
+files ending in .gz or .zst are compressed. Information the conversion or
+the output format loses is reported as warnings.
+";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!("Unknown output format: {name}")),
+        }
+    }
+}
+
+/// Rows of named columns. `null` cells stand for missing values, such as
+/// the parent of a root or the distance to an unreachable node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+    pub columns: Vec<&'static str>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() => f.to_string(),
+            _ => n.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+fn csv_field(text: String) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+impl Table {
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Table => {
+                let cells: Vec<Vec<String>> = self
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|v| {
+                                if v.is_null() {
+                                    "-".to_string()
+                                } else {
+                                    cell_text(v)
+                                }
+                            })
+                            .collect()
+                    })
+                    .collect();
+                let widths: Vec<usize> = (0..self.columns.len())
+                    .map(|c| {
+                        cells
+                            .iter()
+                            .map(|row| row[c].chars().count())
+                            .chain([self.columns[c].len()])
+                            .max()
+                            .unwrap()
+                    })
+                    .collect();
+                let mut out = String::new();
+                let header = self.columns.iter().map(|c| c.to_string());
+                for row in std::iter::once(header.collect()).chain(cells) {
+                    let line: Vec<String> = row
+                        .iter()
+                        .zip(&widths)
+                        .map(|(cell, &width)| format!("{cell:width$}"))
+                        .collect();
+                    writeln!(out, "{}", line.join("  ").trim_end()).unwrap();
+                }
+                out
+            }
+            OutputFormat::Json => {
+                let rows: Vec<Value> = self
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        let object: Map<String, Value> = self
+                            .columns
+                            .iter()
+                            .map(|c| c.to_string())
+                            .zip(row.iter().cloned())
+                            .collect();
+                        Value::Object(object)
+                    })
+                    .collect();
+                format!("{}\n", serde_json::to_string_pretty(&rows).unwrap())
+            }
+            OutputFormat::Csv => {
+                let mut out = format!("{}\n", self.columns.join(","));
+                for row in &self.rows {
+                    let fields: Vec<String> = row.iter().map(|v| csv_field(cell_text(v))).collect();
+                    writeln!(out, "{}", fields.join(",")).unwrap();
+                }
+                out
+            }
+        }
+    }
+}
+
+/// Finds a node by label, or failing that by index.
+pub fn resolve_node(g: &GraphList, name: &str) -> Result<usize, String> {
+    if let Some(node) = g.nodes.iter().find(|node| node.label() == Some(name)) {
+        return Ok(node.index());
+    }
+    match name.parse::<usize>() {
+        Ok(i) if i < g.num_nodes() => Ok(i),
+        _ => Err(format!("No node with label or index `{name}`")),
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Options {
+    pub command: String,
+    pub path: String,
+    pub source: Option<String>,
+    pub target: Option<String>,
+    pub output: OutputFormat,
+}
+
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut positional = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or(format!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "-s" | "--source" => options.source = Some(value()?),
+            "-t" | "--target" => options.target = Some(value()?),
+            "-o" | "--output" => options.output = OutputFormat::parse(&value()?)?,
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("Unknown option: {flag}"))
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+    match positional.as_slice() {
+        [command, path] => {
+            options.command = command.clone();
+            options.path = path.clone();
+            Ok(options)
+        }
+        _ => Err(format!("Expected a command and a graph file\n\n{USAGE}")),
+    }
+}
+
+fn node_columns(g: &GraphList) -> Vec<&'static str> {
+    if g.nodes.iter().any(|node| node.label().is_some()) {
+        vec!["node", "label"]
+    } else {
+        vec!["node"]
+    }
+}
+
+fn node_cells(g: &GraphList, labelled: bool, i: usize) -> Vec<Value> {
+    let mut cells = vec![json!(i)];
+    if labelled {
+        cells.push(g.nodes[i].label().map_or(Value::Null, |l| json!(l)));
+    }
+    cells
+}
+
+// One row per node, with `value` as last column.
+fn per_node(g: &GraphList, column: &'static str, value: impl Fn(usize) -> Value) -> Table {
+    let mut columns = node_columns(g);
+    let labelled = columns.len() > 1;
+    columns.push(column);
+    let rows = (0..g.num_nodes())
+        .map(|i| {
+            let mut row = node_cells(g, labelled, i);
+            row.push(value(i));
+            row
+        })
+        .collect();
+    Table { columns, rows }
+}
+
+fn parent_value(parent: Option<usize>) -> Value {
+    parent.map_or(Value::Null, |parent| json!(parent))
+}
+
+pub(crate) fn distance_value(distance: OrderedFloat<f64>) -> Value {
+    if distance.0.is_finite() {
+        json!(distance.0)
+    } else {
+        Value::Null
+    }
+}
+
+fn path_table(g: &GraphList, path: &[usize]) -> Table {
+    let mut columns = vec!["step"];
+    columns.extend(node_columns(g));
+    let labelled = columns.len() > 2;
+    columns.push("distance");
+    let mut distance = 0.0;
+    let rows = path
+        .iter()
+        .enumerate()
+        .map(|(step, &node)| {
+            if step > 0 {
+                distance += g.get_edge(path[step - 1], node).unwrap().unwrap().weight.0;
+            }
+            let mut row = vec![json!(step)];
+            row.extend(node_cells(g, labelled, node));
+            row.push(json!(distance));
+            row
+        })
+        .collect();
+    Table { columns, rows }
+}
+
+fn degree_rows(rows: &mut Vec<Vec<Value>>, name: &str, degrees: &DegreeStats) {
+    rows.push(vec![json!(format!("min {name}")), json!(degrees.min)]);
+    rows.push(vec![json!(format!("max {name}")), json!(degrees.max)]);
+    rows.push(vec![json!(format!("mean {name}")), json!(degrees.mean)]);
+    for (degree, count) in &degrees.histogram {
+        rows.push(vec![
+            json!(format!("nodes with {name} {degree}")),
+            json!(count),
+        ]);
+    }
+}
+
+fn stats_table(s: &GraphStats) -> Table {
+    let mut rows = vec![
+        vec![json!("nodes"), json!(s.nodes)],
+        vec![json!("edges"), json!(s.edges)],
+        vec![json!("directed"), json!(!s.undirected)],
+        vec![json!("density"), json!(s.density)],
+        vec![json!("components"), json!(s.components)],
+        vec![json!("self-loops"), json!(s.self_loops)],
+        vec![json!("negative edges"), json!(s.negative_edges)],
+        vec![json!("dag"), json!(s.is_dag)],
+    ];
+    match &s.in_degrees {
+        Some(in_degrees) => {
+            degree_rows(&mut rows, "out-degree", &s.degrees);
+            degree_rows(&mut rows, "in-degree", in_degrees);
+        }
+        None => degree_rows(&mut rows, "degree", &s.degrees),
+    }
+    Table {
+        columns: vec!["statistic", "value"],
+        rows,
+    }
+}
+
+fn no_path(g: &GraphList, source: usize, target: usize) -> String {
+    let name = |i: usize| g.nodes[i].label().map_or(i.to_string(), str::to_string);
+    format!("No path from {} to {}", name(source), name(target))
+}
+
+/// Runs an algorithm command on `g`.
+pub fn execute(options: &Options, g: &GraphList) -> Result<Table, String> {
+    let node = |name: &Option<String>| name.as_deref().map(|n| resolve_node(g, n)).transpose();
+    let source = node(&options.source)?;
+    let target = node(&options.target)?;
+    let command = options.command.as_str();
+    let needs_source = || source.ok_or(format!("`{command}` needs --source"));
+    let path = |path: Option<Vec<usize>>, source: usize, target: usize| {
+        path.map(|path| path_table(g, &path))
+            .ok_or_else(|| no_path(g, source, target))
+    };
+    let tree_table = |tree: &ShortestPathTree, column, value: &dyn Fn(usize) -> Value| match target
+    {
+        Some(target) => path(tree.path_to(target), tree.sources()[0], target),
+        None => Ok(per_node(g, column, value)),
+    };
+    match command {
+        "bfs" | "dfs" => {
+            let source = needs_source()?;
+            let tree = if command == "bfs" {
+                bfs_tree(g, source)
+            } else {
+                dfs_tree(g, source)
+            };
+            tree_table(&tree, "parent", &|i| parent_value(tree.parent(i)))
+        }
+        "components" => {
+            let component = dfs_connected_componentes(g);
+            Ok(per_node(g, "component", |i| json!(component[i])))
+        }
+        "scc" => {
+            let components = tarjan_scc(g);
+            Ok(per_node(g, "component", |i| json!(components.component[i])))
+        }
+        "topo" => {
+            let order = topological_sort(g).map_err(|cycle| {
+                let cycle: Vec<String> = cycle.iter().map(usize::to_string).collect();
+                format!("The graph has a cycle: {}", cycle.join(" -> "))
+            })?;
+            let mut columns = vec!["position"];
+            columns.extend(node_columns(g));
+            let labelled = columns.len() > 2;
+            let rows = order
+                .iter()
+                .enumerate()
+                .map(|(position, &i)| {
+                    let mut row = vec![json!(position)];
+                    row.extend(node_cells(g, labelled, i));
+                    row
+                })
+                .collect();
+            Ok(Table { columns, rows })
+        }
+        "bipartite" => {
+            let (_, odd) = bipartition(g).map_err(|cycle| {
+                let cycle: Vec<String> = cycle.iter().map(usize::to_string).collect();
+                format!("The graph has an odd cycle: {}", cycle.join(" - "))
+            })?;
+            Ok(per_node(g, "side", |i| {
+                json!(odd.binary_search(&i).map_or(0, |_| 1))
+            }))
+        }
+        "stats" => Ok(stats_table(&stats(g))),
+        "dijkstra" | "bellman-ford" => {
+            let source = needs_source()?;
+            let (distance, tree) = if command == "dijkstra" {
+                if let Some(e) = g
+                    .make_edge_list()
+                    .into_iter()
+                    .find(|e| e.weight < OrderedFloat(0.0))
+                {
+                    return Err(format!(
+                        "Edge {} -> {} has negative weight, use bellman-ford",
+                        e.from, e.to
+                    ));
+                }
+                dijkstra_tree(g, source)
+            } else {
+                bellman_ford_tree(g, source).ok_or("Negative cycle reachable from the source")?
+            };
+            tree_table(&tree, "distance", &|i| distance_value(distance[i]))
+        }
+        "floyd-warshall" => {
+            let paths = floyd_warshall_paths(g);
+            match (source, target) {
+                (Some(source), Some(target)) => path(paths.path(source, target), source, target),
+                (Some(source), None) => Ok(per_node(g, "predecessor", |i| {
+                    parent_value(paths.predecessor(source, i))
+                })),
+                (None, Some(_)) => Err("`floyd-warshall` needs --source with --target".to_string()),
+                (None, None) => {
+                    let rows = (0..g.num_nodes())
+                        .flat_map(|i| (0..g.num_nodes()).map(move |j| (i, j)))
+                        .map(|(i, j)| {
+                            vec![json!(i), json!(j), parent_value(paths.predecessor(i, j))]
+                        })
+                        .collect();
+                    Ok(Table {
+                        columns: vec!["from", "to", "predecessor"],
+                        rows,
+                    })
+                }
+            }
+        }
+        _ => Err(format!("Unknown command: {command}\n\n{USAGE}")),
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConvertOptions {
+    pub input: String,
+    pub output: String,
+    pub to: Option<Format>,
+    pub relabel: Option<Relabel>,
+    /// Forces the graph to be undirected (`Some(true)`) or directed.
+    pub undirected: Option<bool>,
+    pub drop_weights: bool,
+    pub largest_component: bool,
+}
+
+/// Parses the arguments after `convert`.
+pub fn parse_convert_args(args: &[String]) -> Result<ConvertOptions, String> {
+    let mut options = ConvertOptions::default();
+    let mut positional = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or(format!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--to" => {
+                let name = value()?;
+                options.to =
+                    Some(Format::from_name(&name).ok_or(format!("Unknown format: {name}"))?);
+            }
+            "--relabel" => options.relabel = Some(Relabel::parse(&value()?)?),
+            "--directed" | "--undirected" => {
+                let undirected = arg == "--undirected";
+                if options.undirected.is_some_and(|u| u != undirected) {
+                    return Err("--directed and --undirected exclude each other".to_string());
+                }
+                options.undirected = Some(undirected);
+            }
+            "--drop-weights" => options.drop_weights = true,
+            "--largest-component" => options.largest_component = true,
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("Unknown option: {flag}"))
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+    match positional.as_slice() {
+        [input, output] => {
+            options.input = input.clone();
+            options.output = output.clone();
+            Ok(options)
+        }
+        _ => Err(format!("Expected an input and an output file\n\n{USAGE}")),
+    }
+}
+
+// Undirected edges count once.
+/// Applies the conversion options to `g`, returning the result and what
+/// was lost on the way.
+pub fn transform(options: &ConvertOptions, g: GraphList) -> (GraphList, Vec<String>) {
+    let mut g = g;
+    let mut warnings = vec![];
+    if let Some(mode) = options.relabel {
+        let labels = g.nodes.iter().filter(|node| node.label().is_some()).count();
+        if mode == Relabel::Clear && labels > 0 {
+            warnings.push(format!("removed {labels} node labels"));
+        }
+        relabel(&mut g, mode);
+    }
+    match options.undirected {
+        Some(true) if !g.undirected => {
+            let merged = g
+                .make_edge_list()
+                .iter()
+                .filter(|e| {
+                    e.from < e.to
+                        && g.get_edge(e.to, e.from)
+                            .unwrap()
+                            .is_some_and(|back| back.weight != e.weight)
+                })
+                .count();
+            if merged > 0 {
+                warnings.push(format!(
+                    "merged {merged} pairs of opposite arcs with different weights"
+                ));
+            }
+            g = to_undirected(&g);
+        }
+        Some(false) if g.undirected => g = to_directed(&g),
+        _ => {}
+    }
+    if options.drop_weights {
+        let weighted = g
+            .make_unique_edge_list()
+            .iter()
+            .filter(|e| e.weight != OrderedFloat(1.0))
+            .count();
+        if weighted > 0 {
+            warnings.push(format!("dropped {weighted} edge weights other than 1"));
+        }
+        drop_weights(&mut g);
+    }
+    if options.largest_component {
+        let before = g.num_nodes();
+        g = largest_component(&g);
+        if g.num_nodes() < before {
+            warnings.push(format!(
+                "dropped {} nodes outside the largest component",
+                before - g.num_nodes()
+            ));
+        }
+    }
+    (g, warnings)
+}
+
+/// Runs `convert`, returning a report of what was read, written and lost.
+pub fn convert(options: &ConvertOptions) -> Result<String, String> {
+    let format = options
+        .to
+        .or(Format::from_extension(options.output.as_ref()))
+        .ok_or(format!(
+            "Cannot tell the format of {} from its extension, use --to",
+            options.output
+        ))?;
+    let (g, input_format) = GraphList::load(&options.input)?;
+    let mut report = format!(
+        "Read {} ({input_format}): {} nodes, {} edges\n",
+        options.input,
+        g.num_nodes(),
+        g.num_edges()
+    );
+    let (g, mut warnings) = transform(options, g);
+    warnings.extend(lossy_conversions(&g, format));
+    g.save(&options.output, format)?;
+    writeln!(
+        report,
+        "Wrote {} ({format}): {} nodes, {} edges",
+        options.output,
+        g.num_nodes(),
+        g.num_edges()
+    )
+    .unwrap();
+    for warning in warnings {
+        writeln!(report, "warning: {warning}").unwrap();
+    }
+    Ok(report)
+}
+
+pub fn run(args: &[String]) -> Result<String, String> {
+    if args.is_empty() || args.iter().any(|a| a == "-h" || a == "--help") {
+        return Ok(USAGE.to_string());
+    }
+    if args[0] == "convert" {
+        return convert(&parse_convert_args(&args[1..])?);
+    }
+    if args[0] == "repl" {
+        let mut session = match &args[1..] {
+            [] => Session::new(GraphList::with_nodes(false, 0), None),
+            [path] => Session::new(GraphList::load(path)?.0, Some(path.clone())),
+            _ => return Err(format!("Expected at most one graph file\n\n{USAGE}")),
+        };
+        repl::run(&mut session, io::stdin().lock(), io::stdout()).map_err(|e| e.to_string())?;
+        return Ok(String::new());
+    }
+    if args[0] == "serve" {
+        let (path, address) = match &args[1..] {
+            [path] => (path, "127.0.0.1:7878"),
+            [path, flag, address] if flag == "--listen" => (path, address.as_str()),
+            _ => return Err(format!("Expected a graph file\n\n{USAGE}")),
+        };
+        let (graph, _) = GraphList::load(path)?;
+        let listener =
+            TcpListener::bind(address).map_err(|e| format!("Cannot listen on {address}: {e}"))?;
+        eprintln!("Serving {path} on http://{address}");
+        let state = ServerState {
+            graph,
+            path: Some(path.clone()),
+        };
+        server::serve(listener, Arc::new(RwLock::new(state))).map_err(|e| e.to_string())?;
+        return Ok(String::new());
+    }
+    let options = parse_args(args)?;
+    let (g, _) = GraphList::load(&options.path)?;
+    Ok(execute(&options, &g)?.render(options.output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_graph() -> GraphList {
+        let mut graph = GraphList::with_nodes(false, 0);
+        graph.insert_node(Some("a".to_string()));
+        graph.insert_node(Some("b".to_string()));
+        graph.insert_node(Some("c".to_string()));
+        graph.insert_node(None);
+        graph.insert_edge(0, 1, 4.0).unwrap();
+        graph.insert_edge(0, 2, 1.0).unwrap();
+        graph.insert_edge(2, 1, 2.0).unwrap();
+        graph
+    }
+
+    fn options(command: &str, source: Option<&str>, target: Option<&str>) -> Options {
+        Options {
+            command: command.to_string(),
+            source: source.map(str::to_string),
+            target: target.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = parse_args(&args("bfs g.gml -s a --target 2 -o csv")).unwrap();
+        assert_eq!(parsed.command, "bfs");
+        assert_eq!(parsed.path, "g.gml");
+        assert_eq!(parsed.source.as_deref(), Some("a"));
+        assert_eq!(parsed.target.as_deref(), Some("2"));
+        assert_eq!(parsed.output, OutputFormat::Csv);
+        assert!(parse_args(&args("bfs")).is_err());
+        assert!(parse_args(&args("bfs g.gml -s")).is_err());
+        assert!(parse_args(&args("bfs g.gml -x 1")).is_err());
+        assert!(parse_args(&args("bfs g.gml -o xml")).is_err());
+    }
+
+    #[test]
+    fn test_resolve_node() {
+        let g = create_test_graph();
+        assert_eq!(resolve_node(&g, "c"), Ok(2));
+        assert_eq!(resolve_node(&g, "3"), Ok(3));
+        assert!(resolve_node(&g, "4").is_err());
+        assert!(resolve_node(&g, "d").is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let table = Table {
+            columns: vec!["node", "label", "distance"],
+            rows: vec![
+                vec![json!(0), json!("x, y"), json!(1.5)],
+                vec![json!(10), Value::Null, json!(2.0)],
+            ],
+        };
+        assert_eq!(
+            table.render(OutputFormat::Table),
+            "node  label  distance\n0     x, y   1.5\n10    -      2\n"
+        );
+        assert_eq!(
+            table.render(OutputFormat::Csv),
+            "node,label,distance\n0,\"x, y\",1.5\n10,,2\n"
+        );
+        let parsed: Value = serde_json::from_str(&table.render(OutputFormat::Json)).unwrap();
+        assert_eq!(
+            parsed[1],
+            json!({"node": 10, "label": null, "distance": 2.0})
+        );
+    }
+
+    #[test]
+    fn test_parents() {
+        let g = create_test_graph();
+        let table = execute(&options("bfs", Some("a"), None), &g).unwrap();
+        assert_eq!(table.columns, vec!["node", "label", "parent"]);
+        let parents: Vec<&Value> = table.rows.iter().map(|row| &row[2]).collect();
+        assert_eq!(
+            parents,
+            vec![&Value::Null, &json!(0), &json!(0), &Value::Null]
+        );
+
+        let table = execute(&options("dfs", Some("a"), Some("b")), &g).unwrap();
+        let nodes: Vec<&Value> = table.rows.iter().map(|row| &row[1]).collect();
+        assert_eq!(nodes, vec![&json!(0), &json!(1)]);
+        assert!(execute(&options("bfs", None, None), &g).is_err());
+        assert!(execute(&options("bfs", Some("a"), Some("3")), &g).is_err());
+    }
+
+    #[test]
+    fn test_shortest_paths() {
+        let g = create_test_graph();
+        for command in ["dijkstra", "bellman-ford", "floyd-warshall"] {
+            let table = execute(&options(command, Some("a"), Some("b")), &g).unwrap();
+            let nodes: Vec<&Value> = table.rows.iter().map(|row| &row[1]).collect();
+            assert_eq!(nodes, vec![&json!(0), &json!(2), &json!(1)], "{command}");
+            assert_eq!(table.rows[2][3], json!(3.0));
+        }
+        let table = execute(&options("dijkstra", Some("a"), None), &g).unwrap();
+        let distances: Vec<&Value> = table.rows.iter().map(|row| &row[2]).collect();
+        assert_eq!(
+            distances,
+            vec![&json!(0.0), &json!(3.0), &json!(1.0), &Value::Null]
+        );
+
+        let table = execute(&options("floyd-warshall", None, None), &g).unwrap();
+        assert_eq!(table.rows.len(), 16);
+        assert_eq!(table.rows[1], vec![json!(0), json!(1), json!(2)]);
+
+        let mut negative = create_test_graph();
+        negative.insert_edge(1, 2, -5.0).unwrap();
+        assert!(execute(&options("dijkstra", Some("a"), None), &negative).is_err());
+        assert!(execute(&options("bellman-ford", Some("a"), None), &negative).is_err());
+    }
+
+    #[test]
+    fn test_components() {
+        let mut g = create_test_graph();
+        g.undirected = true;
+        let table = execute(&options("components", None, None), &g).unwrap();
+        let components: Vec<&Value> = table.rows.iter().map(|row| &row[2]).collect();
+        assert_eq!(components, vec![&json!(0), &json!(0), &json!(0), &json!(1)]);
+
+        let mut g = create_test_graph();
+        g.insert_edge(1, 0, 1.0).unwrap();
+        let table = execute(&options("scc", None, None), &g).unwrap();
+        let components: Vec<&Value> = table.rows.iter().map(|row| &row[2]).collect();
+        assert_eq!(components, vec![&json!(0), &json!(0), &json!(0), &json!(1)]);
+    }
+
+    #[test]
+    fn test_topo() {
+        let mut g = create_test_graph();
+        let table = execute(&options("topo", None, None), &g).unwrap();
+        assert_eq!(
+            table.render(OutputFormat::Csv),
+            "position,node,label\n0,0,a\n1,3,\n2,2,c\n3,1,b\n"
+        );
+        g.insert_edge(1, 0, 1.0).unwrap();
+        assert_eq!(
+            execute(&options("topo", None, None), &g).unwrap_err(),
+            "The graph has a cycle: 0 -> 1"
+        );
+    }
+
+    #[test]
+    fn test_bipartite() {
+        let mut g = create_test_graph();
+        assert_eq!(
+            execute(&options("bipartite", None, None), &g).unwrap_err(),
+            "The graph has an odd cycle: 0 - 1 - 2"
+        );
+        g.remove_edge(2, 1).unwrap();
+        let table = execute(&options("bipartite", None, None), &g).unwrap();
+        assert_eq!(
+            table.render(OutputFormat::Csv),
+            "node,label,side\n0,a,0\n1,b,1\n2,c,1\n3,,0\n"
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let g = create_test_graph();
+        let out = execute(&options("stats", None, None), &g)
+            .unwrap()
+            .render(OutputFormat::Table);
+        assert!(out.starts_with("statistic                value\nnodes                    4\n"));
+        assert!(out.contains("\ndirected                 true\n"));
+        assert!(out.contains("\ndag                      true\n"));
+        assert!(out.contains("\nnodes with out-degree 2  1\n"));
+        assert!(out.contains("\nmax in-degree            2\n"));
+    }
+
+    #[test]
+    fn test_run() {
+        let path = std::env::temp_dir().join(format!("algo3-cli-test-{}.gml", std::process::id()));
+        std::fs::write(
+            &path,
+            "graph [ directed 1 node [ id 0 ] node [ id 1 ] edge [ source 0 target 1 weight 2 ] ]",
+        )
+        .unwrap();
+        let line = format!("dijkstra {} --source 0 -o csv", path.display());
+        assert_eq!(run(&args(&line)).unwrap(), "node,distance\n0,0\n1,2\n");
+        std::fs::remove_file(&path).unwrap();
+        assert!(run(&args("dijkstra /nonexistent/graph.gml -s 0")).is_err());
+        assert_eq!(run(&[]).unwrap(), USAGE);
+    }
+
+    #[test]
+    fn test_parse_convert_args() {
+        let options = parse_convert_args(&args(
+            "a.gml b.out --to edge-list --undirected --relabel index",
+        ))
+        .unwrap();
+        assert_eq!(options.input, "a.gml");
+        assert_eq!(options.output, "b.out");
+        assert_eq!(options.to, Some(Format::EdgeList));
+        assert_eq!(options.relabel, Some(Relabel::Index));
+        assert_eq!(options.undirected, Some(true));
+        assert!(!options.drop_weights);
+        assert!(parse_convert_args(&args("a.gml b.net --to dia")).is_err());
+        assert!(parse_convert_args(&args("a.gml b.net --directed --undirected")).is_err());
+        assert!(parse_convert_args(&args("a.gml")).is_err());
+    }
+
+    #[test]
+    fn test_transform() {
+        let mut g = create_test_graph();
+        g.insert_edge(1, 0, 3.0).unwrap();
+        let options = ConvertOptions {
+            relabel: Some(Relabel::Clear),
+            undirected: Some(true),
+            drop_weights: true,
+            largest_component: true,
+            ..Default::default()
+        };
+        let (g, warnings) = transform(&options, g);
+        assert!(g.undirected);
+        assert_eq!(g.num_nodes(), 3);
+        assert_eq!(g.num_edges(), 3);
+        assert!(g.nodes.iter().all(|node| node.label().is_none()));
+        assert_eq!(
+            warnings,
+            [
+                "removed 3 node labels",
+                "merged 1 pairs of opposite arcs with different weights",
+                "dropped 2 edge weights other than 1",
+                "dropped 1 nodes outside the largest component",
+            ]
+        );
+        let (_, warnings) = transform(&ConvertOptions::default(), create_test_graph());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_convert() {
+        let dir = std::env::temp_dir();
+        let input = dir.join(format!("algo3-convert-test-{}.gml", std::process::id()));
+        let output = dir.join(format!("algo3-convert-test-{}.el.gz", std::process::id()));
+        std::fs::write(
+            &input,
+            "graph [ directed 1 node [ id 0 label \"a\" ] node [ id 1 ] edge [ source 0 target 1 weight 2 ] ]",
+        )
+        .unwrap();
+        let line = format!("convert {} {}", input.display(), output.display());
+        let report = run(&args(&line)).unwrap();
+        assert_eq!(
+            report,
+            format!(
+                "Read {} (GML): 2 nodes, 1 edges\nWrote {} (edge list): 2 nodes, 1 edges\nwarning: node labels are not stored in edge list files\n",
+                input.display(),
+                output.display()
+            )
+        );
+        let (g, format) = GraphList::load(&output).unwrap();
+        assert_eq!(format, Format::EdgeList);
+        assert_eq!(g.get_edge(0, 1).unwrap().unwrap().weight, OrderedFloat(2.0));
+        std::fs::remove_file(&output).unwrap();
+
+        let line = format!("convert {} {}.unknown", input.display(), output.display());
+        assert!(run(&args(&line)).is_err());
+        std::fs::remove_file(&input).unwrap();
+    }
+}
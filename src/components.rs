@@ -0,0 +1,91 @@
+use crate::graph::GraphList;
+use crate::union_find::UnionFind;
+use std::collections::HashMap;
+
+/// A partition of the nodes into components, numbered in order of their
+/// lowest node.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Components {
+    /// Component of every node.
+    pub component: Vec<usize>,
+    /// Nodes of every component, in increasing order.
+    pub members: Vec<Vec<usize>>,
+}
+
+impl Components {
+    /// Renumbers any labelling of the nodes where equal labels mean the
+    /// same component.
+    pub fn new(labels: &[usize]) -> Self {
+        let mut number = HashMap::new();
+        let mut components = Components::default();
+        for (node, label) in labels.iter().enumerate() {
+            let c = *number.entry(label).or_insert_with(|| {
+                components.members.push(vec![]);
+                components.members.len() - 1
+            });
+            components.component.push(c);
+            components.members[c].push(node);
+        }
+        components
+    }
+
+    pub fn count(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn sizes(&self) -> Vec<usize> {
+        self.members.iter().map(Vec::len).collect()
+    }
+
+    /// The largest component, the first of them on ties.
+    pub fn largest(&self) -> Option<usize> {
+        (0..self.count())
+            .rev()
+            .max_by_key(|&c| self.members[c].len())
+    }
+}
+
+/// Nodes joined by edges in either direction. For undirected graphs these
+/// are the connected components.
+pub fn weakly_connected_components(g: &GraphList) -> Components {
+    let mut sets = UnionFind::new(g.num_nodes());
+    for e in g.make_edge_list() {
+        sets.union(e.from, e.to);
+    }
+    let roots: Vec<usize> = (0..g.num_nodes()).map(|i| sets.find(i)).collect();
+    Components::new(&roots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weakly_connected_components() {
+        let mut g = GraphList::with_nodes(false, 6);
+        g.insert_edge(1, 0, 1.0).unwrap();
+        g.insert_edge(4, 2, 1.0).unwrap();
+        g.insert_edge(5, 2, 1.0).unwrap();
+        let components = weakly_connected_components(&g);
+        assert_eq!(components.component, vec![0, 0, 1, 2, 1, 1]);
+        assert_eq!(components.members, vec![vec![0, 1], vec![2, 4, 5], vec![3]]);
+        assert_eq!(components.count(), 3);
+        assert_eq!(components.sizes(), vec![2, 3, 1]);
+        assert_eq!(components.largest(), Some(1));
+
+        let components = weakly_connected_components(&GraphList::with_nodes(true, 3));
+        assert_eq!(components.sizes(), vec![1, 1, 1]);
+        assert_eq!(components.largest(), Some(0));
+        assert_eq!(
+            weakly_connected_components(&GraphList::with_nodes(true, 0)).largest(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_new() {
+        let components = Components::new(&[7, 3, 7, 9]);
+        assert_eq!(components.component, vec![0, 1, 0, 2]);
+        assert_eq!(components.members[0], vec![0, 2]);
+    }
+}
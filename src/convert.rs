@@ -0,0 +1,287 @@
+use crate::components::weakly_connected_components;
+use crate::formats::file::Format;
+use crate::graph::GraphList;
+
+// Transformations applied while converting between formats, and the checks
+// for what a format cannot store.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relabel {
+    /// Every node is labelled with its index.
+    Index,
+    /// Labels are removed.
+    Clear,
+}
+
+impl Relabel {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "index" => Ok(Relabel::Index),
+            "clear" | "none" => Ok(Relabel::Clear),
+            _ => Err(format!("Unknown relabelling: {name}")),
+        }
+    }
+}
+
+pub fn relabel(g: &mut GraphList, relabel: Relabel) {
+    for node in &mut g.nodes {
+        node.label = match relabel {
+            Relabel::Index => Some(node.index().to_string()),
+            Relabel::Clear => None,
+        };
+    }
+}
+
+// A copy of the nodes of `g` without edges.
+fn empty_copy(g: &GraphList, undirected: bool) -> GraphList {
+    let mut copy = GraphList::with_nodes(undirected, 0);
+    for node in &g.nodes {
+        copy.insert_node(node.label.clone());
+    }
+    copy
+}
+
+/// The undirected graph with an edge wherever `g` has an arc. Opposite arcs
+/// with different weights become one edge with the smaller weight.
+pub fn to_undirected(g: &GraphList) -> GraphList {
+    if g.undirected {
+        return g.clone();
+    }
+    let mut undirected = empty_copy(g, true);
+    for e in g.make_edge_list() {
+        let weight = match g.get_edge(e.to, e.from).unwrap() {
+            Some(back) => e.weight.min(back.weight),
+            None => e.weight,
+        };
+        undirected.insert_edge(e.from, e.to, weight.0).unwrap();
+    }
+    undirected
+}
+
+/// The directed graph with arcs both ways for every edge of `g`.
+pub fn to_directed(g: &GraphList) -> GraphList {
+    // Undirected graphs already store both arcs of every edge.
+    GraphList {
+        undirected: false,
+        ..g.clone()
+    }
+}
+
+/// Sets every weight to 1.
+pub fn drop_weights(g: &mut GraphList) {
+    for e in g
+        .make_edge_list()
+        .iter()
+        .map(|e| (e.from, e.to))
+        .collect::<Vec<_>>()
+    {
+        g.nodes[e.0].add_edge(e.1, 1.0);
+    }
+}
+
+/// The subgraph induced by the largest weakly connected component, with
+/// its nodes renumbered in their original order. Ties go to the component
+/// of the lowest node.
+pub fn largest_component(g: &GraphList) -> GraphList {
+    let components = weakly_connected_components(g);
+    let Some(largest) = components.largest() else {
+        return g.clone();
+    };
+    let kept = &components.members[largest];
+    let mut index = vec![None; g.num_nodes()];
+    let mut sub = GraphList::with_nodes(g.undirected, 0);
+    for (new, &old) in kept.iter().enumerate() {
+        index[old] = Some(new);
+        sub.insert_node(g.nodes[old].label.clone());
+    }
+    for e in g.make_edge_list() {
+        if let (Some(from), Some(to)) = (index[e.from], index[e.to]) {
+            sub.nodes[from].add_edge(to, e.weight.0);
+        }
+    }
+    sub
+}
+
+/// What of `g` would be lost or changed by writing it as `format` and
+/// reading it back.
+pub fn lossy_conversions(g: &GraphList, format: Format) -> Vec<String> {
+    let mut warnings = vec![];
+    let edges = g.make_edge_list();
+    let labelled = g.nodes.iter().any(|node| node.label().is_some());
+    match format {
+        Format::MatrixMarket
+        | Format::Metis
+        | Format::Snap
+        | Format::EdgeList
+        | Format::Matrix
+        | Format::Dimacs
+            if labelled =>
+        {
+            warnings.push(format!("node labels are not stored in {format} files"))
+        }
+        Format::Pajek
+            if g.nodes
+                .iter()
+                .any(|n| n.label().is_some_and(|l| l.contains('"'))) =>
+        {
+            warnings.push("double quotes in labels become single quotes".to_string())
+        }
+        Format::Csv if g.nodes.iter().any(|node| node.label().is_none()) => {
+            warnings.push("unlabelled nodes are labelled with their index".to_string())
+        }
+        _ => {}
+    }
+    match format {
+        Format::EdgeList | Format::Csv if g.undirected => warnings
+            .push("undirected edges are written once and read back as directed arcs".to_string()),
+        Format::Matrix
+            if !g.undirected
+                && edges.iter().all(|e| {
+                    g.get_edge(e.to, e.from).unwrap().map(|back| back.weight) == Some(e.weight)
+                }) =>
+        {
+            warnings.push("symmetric directed graphs are read back as undirected".to_string())
+        }
+        Format::Dimacs if g.undirected && edges.iter().any(|e| e.weight.0 != 1.0) => {
+            warnings.push("undirected DIMACS files have no edge weights".to_string())
+        }
+        _ => {}
+    }
+    if matches!(format, Format::Snap | Format::Csv) {
+        let mut isolated = vec![true; g.num_nodes()];
+        for e in &edges {
+            isolated[e.from] = false;
+            isolated[e.to] = false;
+        }
+        let isolated = isolated.iter().filter(|&&i| i).count();
+        if isolated > 0 {
+            warnings.push(format!(
+                "{isolated} isolated nodes are not stored in {format} files"
+            ));
+        }
+    }
+    if matches!(format, Format::Mermaid | Format::PlantUml | Format::Svg) {
+        warnings.push(format!("{format} files cannot be read back"));
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    fn create_test_graph() -> GraphList {
+        let mut graph = GraphList::with_nodes(false, 0);
+        graph.insert_node(Some("a".to_string()));
+        graph.insert_node(None);
+        graph.insert_node(Some("c".to_string()));
+        graph.insert_node(None);
+        graph.insert_node(None);
+        graph.insert_edge(0, 1, 4.0).unwrap();
+        graph.insert_edge(1, 0, 2.0).unwrap();
+        graph.insert_edge(2, 1, 3.0).unwrap();
+        graph.insert_edge(3, 4, 1.0).unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_relabel() {
+        let mut g = create_test_graph();
+        relabel(&mut g, Relabel::Index);
+        assert_eq!(g.nodes[1].label(), Some("1"));
+        relabel(&mut g, Relabel::Clear);
+        assert!(g.nodes.iter().all(|node| node.label().is_none()));
+        assert!(Relabel::parse("labels").is_err());
+    }
+
+    #[test]
+    fn test_direction() {
+        let g = to_undirected(&create_test_graph());
+        assert!(g.undirected);
+        assert_eq!(g.get_edge(0, 1).unwrap().unwrap().weight, OrderedFloat(2.0));
+        assert_eq!(g.get_edge(1, 2).unwrap().unwrap().weight, OrderedFloat(3.0));
+        assert_eq!(g.nodes[2].label(), Some("c"));
+        let g = to_directed(&g);
+        assert!(!g.undirected);
+        assert!(g.is_edge(1, 2) && g.is_edge(2, 1));
+        assert_eq!(g.make_edge_list().len(), 6);
+    }
+
+    #[test]
+    fn test_drop_weights() {
+        let mut g = to_undirected(&create_test_graph());
+        drop_weights(&mut g);
+        assert!(g
+            .make_edge_list()
+            .iter()
+            .all(|e| e.weight == OrderedFloat(1.0)));
+        assert_eq!(g.make_edge_list().len(), 6);
+    }
+
+    #[test]
+    fn test_largest_component() {
+        let g = largest_component(&create_test_graph());
+        assert_eq!(g.num_nodes(), 3);
+        assert!(!g.undirected);
+        assert_eq!(g.nodes[2].label(), Some("c"));
+        assert!(g.is_edge(2, 1) && !g.is_edge(1, 2));
+        assert_eq!(g.get_edge(1, 0).unwrap().unwrap().weight, OrderedFloat(2.0));
+
+        let mut tie = GraphList::with_nodes(true, 4);
+        tie.insert_edge(2, 3, 1.0).unwrap();
+        tie.insert_edge(0, 1, 1.0).unwrap();
+        let g = largest_component(&tie);
+        assert_eq!(g.num_nodes(), 2);
+        assert!(g.is_edge(1, 0));
+        assert_eq!(
+            largest_component(&GraphList::with_nodes(true, 0)).num_nodes(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_lossy_conversions() {
+        let g = create_test_graph();
+        assert!(lossy_conversions(&g, Format::Gml).is_empty());
+        assert!(lossy_conversions(&g, Format::Binary).is_empty());
+        assert_eq!(lossy_conversions(&g, Format::EdgeList).len(), 1);
+        assert_eq!(
+            lossy_conversions(&g, Format::Snap),
+            ["node labels are not stored in SNAP files"]
+        );
+        let mut bare = to_undirected(&g);
+        relabel(&mut bare, Relabel::Clear);
+        bare.insert_node(None);
+        assert_eq!(
+            lossy_conversions(&bare, Format::EdgeList),
+            ["undirected edges are written once and read back as directed arcs"]
+        );
+        assert_eq!(
+            lossy_conversions(&bare, Format::Snap),
+            ["1 isolated nodes are not stored in SNAP files"]
+        );
+        assert!(lossy_conversions(&bare, Format::Matrix).is_empty());
+        assert_eq!(
+            lossy_conversions(&to_directed(&bare), Format::Matrix),
+            ["symmetric directed graphs are read back as undirected"]
+        );
+        assert_eq!(
+            lossy_conversions(&bare, Format::Csv),
+            [
+                "unlabelled nodes are labelled with their index",
+                "undirected edges are written once and read back as directed arcs",
+                "1 isolated nodes are not stored in CSV files"
+            ]
+        );
+        assert_eq!(
+            lossy_conversions(&bare, Format::Dimacs),
+            ["undirected DIMACS files have no edge weights"]
+        );
+        assert!(lossy_conversions(&g, Format::Dot).is_empty());
+        assert_eq!(
+            lossy_conversions(&bare, Format::Svg),
+            ["SVG files cannot be read back"]
+        );
+    }
+}
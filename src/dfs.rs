@@ -1,16 +1,34 @@
-use crate::graph::{GraphList, GraphMatrix, Node};
+use crate::components::weakly_connected_components;
+use crate::graph::{Edge, GraphList};
+use crate::visitor::Visitor;
+use std::collections::HashMap;
 
-pub fn dfs_recursive(g: &GraphList, ind: usize, seen: &mut Vec<bool>) {
-    seen[ind] = true;
-    let current = &g.nodes[ind];
-
-    for e in current.get_edge_list() {
-        let neighbour = e.to;
-        if !seen[neighbour] {
-            dfs_recursive(g, neighbour, seen)
+// Explores from `start` in the order of a recursive DFS, keeping the edges
+// left to look at of every node on the path in place of the call stack.
+// `enter` is called on every neighbor found and returns whether it is new,
+// in which case it is explored next. `start` must already be entered.
+fn explore(g: &GraphList, start: usize, mut enter: impl FnMut(usize) -> bool) {
+    let mut stack = vec![g.nodes[start].get_edge_list().into_iter()];
+    while let Some(edges) = stack.last_mut() {
+        match edges.next() {
+            Some(e) => {
+                if enter(e.to) {
+                    stack.push(g.nodes[e.to].get_edge_list().into_iter());
+                }
+            }
+            None => {
+                stack.pop();
+            }
         }
     }
 }
+
+pub fn dfs_recursive(g: &GraphList, ind: usize, seen: &mut [bool]) {
+    seen[ind] = true;
+    explore(g, ind, |neighbour| {
+        !std::mem::replace(&mut seen[neighbour], true)
+    });
+}
 pub fn dfs(g: &GraphList, start: usize) {
     let mut seen = vec![false; g.num_nodes()];
     dfs_recursive(g, start, &mut seen);
@@ -30,8 +48,7 @@ pub fn dfs_stack(g: &GraphList, start: usize) -> Vec<i64> {
     // This is the 'stack'
     let mut to_explore = Vec::new();
     to_explore.push(start);
-    while !to_explore.is_empty() {
-        let ind = to_explore.pop().unwrap();
+    while let Some(ind) = to_explore.pop() {
         if !seen[ind] {
             let current = &g.nodes[ind];
             seen[ind] = true;
@@ -46,25 +63,219 @@ pub fn dfs_stack(g: &GraphList, start: usize) -> Vec<i64> {
             }
         }
     }
-    return last;
+    last
+}
+
+/// Nodes reachable from a start node in depth-first preorder, computed as
+/// they are asked for. The order is that of a recursive DFS taking edges
+/// in order of their targets.
+pub struct Dfs<'a> {
+    g: &'a GraphList,
+    seen: Vec<bool>,
+    to_explore: Vec<usize>,
+}
+
+impl<'a> Dfs<'a> {
+    pub fn new(g: &'a GraphList, start: usize) -> Self {
+        Dfs {
+            g,
+            seen: vec![false; g.num_nodes()],
+            to_explore: vec![start],
+        }
+    }
+}
+
+impl Iterator for Dfs<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let mut next = self.to_explore.pop()?;
+        while self.seen[next] {
+            next = self.to_explore.pop()?;
+        }
+        self.seen[next] = true;
+        for e in self.g.nodes[next].get_ordered_edge_list().into_iter().rev() {
+            if !self.seen[e.to] {
+                self.to_explore.push(e.to);
+            }
+        }
+        Some(next)
+    }
+}
+
+// Discovery times, with `finished` telling ancestors still on the stack
+// from finished nodes.
+struct VisitState {
+    discovered: Vec<Option<usize>>,
+    finished: Vec<bool>,
+    time: usize,
+}
+
+impl VisitState {
+    fn new(g: &GraphList) -> Self {
+        VisitState {
+            discovered: vec![None; g.num_nodes()],
+            finished: vec![false; g.num_nodes()],
+            time: 0,
+        }
+    }
+
+    fn discover(&mut self, node: usize, visitor: &mut impl Visitor) {
+        self.discovered[node] = Some(self.time);
+        self.time += 1;
+        visitor.discover(node);
+    }
+
+    // Explores from `start` with a stack of (node, edges, next edge) in
+    // place of recursion.
+    fn visit(&mut self, g: &GraphList, start: usize, visitor: &mut impl Visitor) {
+        self.discover(start, visitor);
+        let mut stack = vec![(start, g.nodes[start].get_ordered_edge_list(), 0)];
+        while let Some((node, edges, next)) = stack.last_mut() {
+            let node = *node;
+            let Some(&e) = edges.get(*next) else {
+                self.finished[node] = true;
+                visitor.finish(node);
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+            visitor.examine_edge(e);
+            match self.discovered[e.to] {
+                None => {
+                    visitor.tree_edge(e);
+                    self.discover(e.to, visitor);
+                    stack.push((e.to, g.nodes[e.to].get_ordered_edge_list(), 0));
+                }
+                Some(_) if !self.finished[e.to] => visitor.back_edge(e),
+                Some(time) if time > self.discovered[node].unwrap() => visitor.forward_edge(e),
+                Some(_) => visitor.cross_edge(e),
+            }
+        }
+    }
+}
+
+/// Depth-first search from `start`, reporting its events to `visitor`.
+/// Edges are examined in order of their targets.
+pub fn dfs_visit(g: &GraphList, start: usize, visitor: &mut impl Visitor) {
+    VisitState::new(g).visit(g, start, visitor);
+}
+
+/// Like `dfs_visit`, restarting from the lowest undiscovered node until
+/// every node is discovered.
+pub fn dfs_visit_all(g: &GraphList, visitor: &mut impl Visitor) {
+    let mut state = VisitState::new(g);
+    for start in 0..g.num_nodes() {
+        if state.discovered[start].is_none() {
+            state.visit(g, start, visitor);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeKind {
+    Tree,
+    Back,
+    Forward,
+    Cross,
+}
+
+/// Pre and post-order numbering of a DFS over the whole graph, restarting
+/// from the lowest undiscovered node as `dfs_visit_all` does.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DfsTimes {
+    /// Discoveries and finishes share one clock, starting at 0, so the
+    /// interval of a node contains those of its descendants.
+    pub discovered: Vec<usize>,
+    pub finished: Vec<usize>,
+    pub parent: Vec<Option<usize>>,
+    pub preorder: Vec<usize>,
+    pub postorder: Vec<usize>,
+    /// The kind of every edge. Undirected edges are seen from both ends, so
+    /// a tree edge is also a back edge from the child to its parent.
+    pub edges: HashMap<(usize, usize), EdgeKind>,
+}
+
+impl DfsTimes {
+    pub fn kind(&self, from: usize, to: usize) -> Option<EdgeKind> {
+        self.edges.get(&(from, to)).copied()
+    }
+}
+
+#[derive(Default)]
+struct Timer {
+    times: DfsTimes,
+    clock: usize,
+}
+
+impl Timer {
+    fn tick(&mut self) -> usize {
+        self.clock += 1;
+        self.clock - 1
+    }
+}
+
+impl Visitor for Timer {
+    fn discover(&mut self, node: usize) {
+        self.times.discovered[node] = self.tick();
+        self.times.preorder.push(node);
+    }
+    fn tree_edge(&mut self, e: &Edge) {
+        self.times.parent[e.to] = Some(e.from);
+        self.times.edges.insert((e.from, e.to), EdgeKind::Tree);
+    }
+    fn back_edge(&mut self, e: &Edge) {
+        self.times.edges.insert((e.from, e.to), EdgeKind::Back);
+    }
+    fn forward_edge(&mut self, e: &Edge) {
+        self.times.edges.insert((e.from, e.to), EdgeKind::Forward);
+    }
+    fn cross_edge(&mut self, e: &Edge) {
+        self.times.edges.insert((e.from, e.to), EdgeKind::Cross);
+    }
+    fn finish(&mut self, node: usize) {
+        self.times.finished[node] = self.tick();
+        self.times.postorder.push(node);
+    }
+}
+
+pub fn dfs_times(g: &GraphList) -> DfsTimes {
+    let n = g.num_nodes();
+    let mut timer = Timer::default();
+    timer.times.discovered = vec![0; n];
+    timer.times.finished = vec![0; n];
+    timer.times.parent = vec![None; n];
+    dfs_visit_all(g, &mut timer);
+    timer.times
 }
 
 pub fn dfs_recursive_connected_componentes(
     g: &GraphList,
     ind: usize,
-    component: &mut Vec<isize>,
+    component: &mut [isize],
     curr_comp: isize,
 ) {
     component[ind] = curr_comp;
-    for e in g.nodes[ind].get_edge_list() {
-        let neighbor = e.to;
-        if component[neighbor] == -1 {
-            dfs_recursive_connected_componentes(g, neighbor, component, curr_comp)
+    explore(g, ind, |neighbor| {
+        let new = component[neighbor] == -1;
+        if new {
+            component[neighbor] = curr_comp;
         }
-    }
+        new
+    });
 }
 
+/// Connected components, numbered in order of their lowest node. Directed
+/// graphs get their weakly connected components, as DFS along outgoing
+/// edges alone would split them depending on the node order.
 pub fn dfs_connected_componentes(g: &GraphList) -> Vec<isize> {
+    if !g.undirected {
+        return weakly_connected_components(g)
+            .component
+            .into_iter()
+            .map(|c| c as isize)
+            .collect();
+    }
     let mut component = vec![-1; g.num_nodes()];
     let mut curr_comp = 0;
     for ind in 0..g.num_nodes() {
@@ -73,12 +284,13 @@ pub fn dfs_connected_componentes(g: &GraphList) -> Vec<isize> {
             curr_comp += 1;
         }
     }
-    return component;
+    component
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::graph::Node;
     #[test]
     fn test_dfs_functions() {
         let mut graph = GraphList {
@@ -107,6 +319,11 @@ mod tests {
         graph.insert_node(None);
         let components = dfs_connected_componentes(&graph);
         assert_eq!(components, vec![0, 0, 0, 1]);
+
+        // Edges against the node order still join components.
+        graph.insert_edge(3, 0, 1.0).unwrap();
+        let components = dfs_connected_componentes(&graph);
+        assert_eq!(components, vec![0, 0, 0, 0]);
     }
 
     #[test]
@@ -139,4 +356,179 @@ mod tests {
         let components = dfs_connected_componentes(&graph);
         assert_eq!(components, vec![0]);
     }
+
+    #[test]
+    fn test_dfs_iterator() {
+        let mut graph = GraphList::with_nodes(false, 5);
+        graph.insert_edge(0, 2, 1.0).unwrap();
+        graph.insert_edge(0, 1, 1.0).unwrap();
+        graph.insert_edge(1, 3, 1.0).unwrap();
+        graph.insert_edge(3, 2, 1.0).unwrap();
+        graph.insert_edge(2, 4, 1.0).unwrap();
+        assert_eq!(Dfs::new(&graph, 0).collect::<Vec<_>>(), vec![0, 1, 3, 2, 4]);
+        assert_eq!(Dfs::new(&graph, 4).collect::<Vec<_>>(), vec![4]);
+        assert_eq!(Dfs::new(&graph, 0).take(2).collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    #[derive(Default)]
+    struct Events(Vec<String>);
+
+    impl Visitor for Events {
+        fn discover(&mut self, node: usize) {
+            self.0.push(format!("discover {node}"));
+        }
+        fn tree_edge(&mut self, e: &Edge) {
+            self.0.push(format!("tree {} {}", e.from, e.to));
+        }
+        fn back_edge(&mut self, e: &Edge) {
+            self.0.push(format!("back {} {}", e.from, e.to));
+        }
+        fn forward_edge(&mut self, e: &Edge) {
+            self.0.push(format!("forward {} {}", e.from, e.to));
+        }
+        fn cross_edge(&mut self, e: &Edge) {
+            self.0.push(format!("cross {} {}", e.from, e.to));
+        }
+        fn finish(&mut self, node: usize) {
+            self.0.push(format!("finish {node}"));
+        }
+    }
+
+    #[test]
+    fn test_dfs_visit() {
+        let mut graph = GraphList::with_nodes(false, 4);
+        graph.insert_edge(0, 1, 1.0).unwrap();
+        graph.insert_edge(1, 2, 1.0).unwrap();
+        graph.insert_edge(2, 0, 1.0).unwrap();
+        graph.insert_edge(0, 2, 1.0).unwrap();
+        graph.insert_edge(3, 1, 1.0).unwrap();
+        let mut events = Events::default();
+        dfs_visit(&graph, 0, &mut events);
+        assert_eq!(
+            events.0,
+            [
+                "discover 0",
+                "tree 0 1",
+                "discover 1",
+                "tree 1 2",
+                "discover 2",
+                "back 2 0",
+                "finish 2",
+                "finish 1",
+                "forward 0 2",
+                "finish 0",
+            ]
+        );
+
+        let mut events = Events::default();
+        dfs_visit_all(&graph, &mut events);
+        assert_eq!(events.0[10..], ["discover 3", "cross 3 1", "finish 3"]);
+    }
+
+    #[test]
+    fn test_dfs_times() {
+        // 0 -> 1 -> 2 -> 0, 0 -> 2, 3 -> 1, 3 -> 4
+        let mut graph = GraphList::with_nodes(false, 5);
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (0, 2), (3, 1), (3, 4)] {
+            graph.insert_edge(from, to, 1.0).unwrap();
+        }
+        let times = dfs_times(&graph);
+        assert_eq!(times.discovered, vec![0, 1, 2, 6, 7]);
+        assert_eq!(times.finished, vec![5, 4, 3, 9, 8]);
+        assert_eq!(times.parent, vec![None, Some(0), Some(1), None, Some(3)]);
+        assert_eq!(times.preorder, vec![0, 1, 2, 3, 4]);
+        assert_eq!(times.postorder, vec![2, 1, 0, 4, 3]);
+        assert_eq!(times.edges.len(), 6);
+        assert_eq!(times.kind(0, 1), Some(EdgeKind::Tree));
+        assert_eq!(times.kind(2, 0), Some(EdgeKind::Back));
+        assert_eq!(times.kind(0, 2), Some(EdgeKind::Forward));
+        assert_eq!(times.kind(3, 1), Some(EdgeKind::Cross));
+        assert_eq!(times.kind(3, 4), Some(EdgeKind::Tree));
+        assert_eq!(times.kind(1, 0), None);
+
+        let mut undirected = GraphList::with_nodes(true, 2);
+        undirected.insert_edge(0, 1, 1.0).unwrap();
+        let times = dfs_times(&undirected);
+        assert_eq!(times.kind(0, 1), Some(EdgeKind::Tree));
+        assert_eq!(times.kind(1, 0), Some(EdgeKind::Back));
+        assert_eq!(
+            dfs_times(&GraphList::with_nodes(false, 0)),
+            DfsTimes::default()
+        );
+    }
+
+    #[test]
+    fn test_million_node_chain() {
+        let n = 1_000_000;
+        let mut graph = GraphList::with_nodes(false, n);
+        for i in 0..n - 1 {
+            graph.insert_edge(i, i + 1, 1.0).unwrap();
+        }
+        let mut seen = vec![false; n];
+        dfs_recursive(&graph, 0, &mut seen);
+        assert!(seen.iter().all(|&s| s));
+        dfs(&graph, 0);
+        dfs_all(&graph);
+        assert!(dfs_connected_componentes(&graph).iter().all(|&c| c == 0));
+        assert_eq!(dfs_stack(&graph, 0)[n - 1], n as i64 - 2);
+        assert_eq!(Dfs::new(&graph, 0).last(), Some(n - 1));
+
+        struct Depth(usize, usize);
+        impl Visitor for Depth {
+            fn tree_edge(&mut self, _: &Edge) {
+                self.0 += 1;
+                self.1 = self.1.max(self.0);
+            }
+            fn finish(&mut self, _: usize) {
+                self.0 = self.0.saturating_sub(1);
+            }
+        }
+        let mut depth = Depth(0, 0);
+        dfs_visit_all(&graph, &mut depth);
+        assert_eq!(depth.1, n - 1);
+    }
+
+    #[test]
+    fn test_recursive_order() {
+        // Records the order the old recursive implementation visited nodes
+        // in, which `explore` keeps.
+        fn recursive(g: &GraphList, ind: usize, seen: &mut Vec<bool>, order: &mut Vec<usize>) {
+            seen[ind] = true;
+            order.push(ind);
+            for e in g.nodes[ind].get_edge_list() {
+                if !seen[e.to] {
+                    recursive(g, e.to, seen, order)
+                }
+            }
+        }
+        let mut graph = GraphList::with_nodes(false, 8);
+        for (from, to) in [
+            (0, 3),
+            (0, 1),
+            (1, 4),
+            (3, 4),
+            (4, 2),
+            (2, 0),
+            (5, 6),
+            (6, 7),
+            (7, 5),
+            (3, 6),
+        ] {
+            graph.insert_edge(from, to, 1.0).unwrap();
+        }
+        let mut expected = vec![];
+        recursive(&graph, 0, &mut vec![false; 8], &mut expected);
+        let mut order = vec![];
+        let mut seen = [false; 8];
+        seen[0] = true;
+        order.push(0);
+        explore(&graph, 0, |i| {
+            let new = !std::mem::replace(&mut seen[i], true);
+            if new {
+                order.push(i);
+            }
+            new
+        });
+        assert_eq!(order, expected);
+    }
 }
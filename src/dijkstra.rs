@@ -1,6 +1,6 @@
-use crate::graph::{GraphList, Node};
+use crate::graph::GraphList;
 use ordered_float::OrderedFloat;
-use std::cmp::{self, Ordering, Reverse};
+use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 // Boilerplate for state + min heap taken from here:
 // https://doc.rust-lang.org/nightly/std/collections/binary_heap/index.html
@@ -69,11 +69,12 @@ pub fn dijkstra(g: &GraphList, start: usize) -> Vec<OrderedFloat<f64>> {
             }
         }
     }
-    return costs;
+    costs
 }
 #[cfg(test)]
 mod dijkstra_tests {
     use super::*;
+    use crate::graph::Node;
     use ordered_float::OrderedFloat;
 
     fn create_weighted_graph() -> GraphList {
@@ -127,7 +128,7 @@ mod dijkstra_tests {
 
     #[test]
     fn test_all_nodes_unreachable() {
-        let mut graph = GraphList {
+        let graph = GraphList {
             undirected: false,
             nodes: vec![Node::new(0, None), Node::new(1, None)],
         };
@@ -1,6 +1,6 @@
 use ordered_float::OrderedFloat;
 
-use crate::graph::{Edge, GraphList, Node};
+use crate::graph::{Edge, GraphList};
 
 pub fn floyd_warshall(g: &GraphList) -> Vec<Vec<isize>> {
     let n = g.num_nodes();
@@ -29,5 +29,5 @@ pub fn floyd_warshall(g: &GraphList) -> Vec<Vec<isize>> {
             }
         }
     }
-    return last;
+    last
 }
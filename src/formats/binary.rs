@@ -0,0 +1,486 @@
+//! Compact binary format for `GraphList`.
+//!
+//! All integers are little-endian. A file is a fixed 36 byte header, a body
+//! and a 4 byte trailer:
+//!
+//! | offset | size | field                                             |
+//! |--------|------|---------------------------------------------------|
+//! | 0      | 4    | magic, `b"AG3G"`                                  |
+//! | 4      | 2    | format version, currently 1                       |
+//! | 6      | 2    | flags: bit 0 undirected, bit 1 labels present     |
+//! | 8      | 8    | number of nodes `n`                               |
+//! | 16     | 8    | number of stored edges `m`                        |
+//! | 24     | 8    | size in bytes of the label section `l`            |
+//! | 32     | 4    | CRC-32 of bytes 0..32                             |
+//!
+//! The body holds, in order:
+//!
+//! - the label section (`l` bytes, only when bit 1 is set): for each node a
+//!   `u32` length followed by that many bytes of UTF-8, or `u32::MAX` and no
+//!   bytes for a node without a label;
+//! - `n + 1` `u64` CSR offsets: the edges of node `i` are entries
+//!   `offsets[i]..offsets[i + 1]`, with `offsets[0] == 0` and `offsets[n] == m`;
+//! - `m` `u32` edge targets, strictly increasing within each node;
+//! - `m` `f64` edge weights.
+//!
+//! The trailer is the CRC-32 of the body. Undirected graphs store each edge
+//! once, in the row of its smaller endpoint.
+
+use crate::graph::GraphList;
+use std::io::{self, Write};
+
+const MAGIC: &[u8; 4] = b"AG3G";
+const VERSION: u16 = 1;
+const FLAG_UNDIRECTED: u16 = 1;
+const FLAG_LABELS: u16 = 1 << 1;
+const HEADER_LEN: usize = 36;
+const TRAILER_LEN: usize = 4;
+const NO_LABEL: u32 = u32::MAX;
+
+// CRC-32 (IEEE 802.3), the same checksum used by gzip and zip.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0_u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+#[derive(Clone, Copy)]
+struct Crc32(u32);
+
+impl Crc32 {
+    fn new() -> Self {
+        Crc32(!0)
+    }
+    fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = CRC_TABLE[((self.0 ^ b as u32) & 0xFF) as usize] ^ (self.0 >> 8);
+        }
+    }
+    fn finish(self) -> u32 {
+        !self.0
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(bytes);
+    crc.finish()
+}
+
+// Checksums everything written through it.
+struct CrcWriter<'a, W: Write> {
+    inner: &'a mut W,
+    crc: Crc32,
+}
+
+impl<W: Write> CrcWriter<'_, W> {
+    fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.crc.update(bytes);
+        self.inner.write_all(bytes)
+    }
+}
+
+// Edges as stored in the file: every arc of a directed graph, only `to >= from`
+// arcs of an undirected one.
+fn stored_edges(g: &GraphList) -> Vec<Vec<(usize, f64)>> {
+    g.nodes
+        .iter()
+        .map(|node| {
+            node.get_ordered_edge_list()
+                .into_iter()
+                .filter(|e| !g.undirected || e.to >= e.from)
+                .map(|e| (e.to, e.weight.0))
+                .collect()
+        })
+        .collect()
+}
+
+/// Writes `g` in the binary format. Fails if the graph has more than
+/// `u32::MAX` nodes or a label longer than `u32::MAX - 1` bytes.
+pub fn write<W: Write>(g: &GraphList, w: &mut W) -> io::Result<()> {
+    let n = g.num_nodes();
+    if n > u32::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Too many nodes for the binary format: {n}"),
+        ));
+    }
+    let edges = stored_edges(g);
+    let m: usize = edges.iter().map(Vec::len).sum();
+    let has_labels = g.nodes.iter().any(|node| node.label().is_some());
+    let mut labels_len = 0_u64;
+    if has_labels {
+        for node in &g.nodes {
+            let len = node.label().map_or(0, str::len);
+            if len >= NO_LABEL as usize {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Label of node {} is too long", node.index()),
+                ));
+            }
+            labels_len += 4 + len as u64;
+        }
+    }
+
+    let mut flags = 0;
+    if g.undirected {
+        flags |= FLAG_UNDIRECTED;
+    }
+    if has_labels {
+        flags |= FLAG_LABELS;
+    }
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&VERSION.to_le_bytes());
+    header.extend_from_slice(&flags.to_le_bytes());
+    header.extend_from_slice(&(n as u64).to_le_bytes());
+    header.extend_from_slice(&(m as u64).to_le_bytes());
+    header.extend_from_slice(&labels_len.to_le_bytes());
+    header.extend_from_slice(&crc32(&header).to_le_bytes());
+    w.write_all(&header)?;
+
+    let mut body = CrcWriter {
+        inner: w,
+        crc: Crc32::new(),
+    };
+    if has_labels {
+        for node in &g.nodes {
+            match node.label() {
+                Some(label) => {
+                    body.write_all(&(label.len() as u32).to_le_bytes())?;
+                    body.write_all(label.as_bytes())?;
+                }
+                None => body.write_all(&NO_LABEL.to_le_bytes())?,
+            }
+        }
+    }
+    let mut offset = 0_u64;
+    body.write_all(&offset.to_le_bytes())?;
+    for row in &edges {
+        offset += row.len() as u64;
+        body.write_all(&offset.to_le_bytes())?;
+    }
+    for &(to, _) in edges.iter().flatten() {
+        body.write_all(&(to as u32).to_le_bytes())?;
+    }
+    for &(_, weight) in edges.iter().flatten() {
+        body.write_all(&weight.to_le_bytes())?;
+    }
+    let crc = body.crc.finish();
+    w.write_all(&crc.to_le_bytes())
+}
+
+pub fn to_bytes(g: &GraphList) -> Vec<u8> {
+    let mut bytes = vec![];
+    write(g, &mut bytes).unwrap();
+    bytes
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes(bytes[at..at + 2].try_into().unwrap())
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn u64_at(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+fn read_labels(section: &[u8], n: usize) -> Result<Vec<Option<String>>, String> {
+    let mut labels = Vec::with_capacity(n);
+    let mut at = 0;
+    for i in 0..n {
+        if section.len() - at < 4 {
+            return Err(format!("Label section ends before the label of node {i}"));
+        }
+        let len = u32_at(section, at);
+        at += 4;
+        if len == NO_LABEL {
+            labels.push(None);
+            continue;
+        }
+        let len = len as usize;
+        if section.len() - at < len {
+            return Err(format!("Label of node {i} runs past the label section"));
+        }
+        let label = std::str::from_utf8(&section[at..at + len])
+            .map_err(|_| format!("Label of node {i} is not valid UTF-8"))?;
+        labels.push(Some(label.to_string()));
+        at += len;
+    }
+    if at != section.len() {
+        return Err("Unexpected bytes at the end of the label section".to_string());
+    }
+    Ok(labels)
+}
+
+/// Reads a graph written by [`write`], validating the header, both
+/// checksums and the structure of the edge arrays.
+pub fn read(bytes: &[u8]) -> Result<GraphList, String> {
+    if bytes.len() < 4 || &bytes[..4] != MAGIC {
+        return Err("Not a binary graph file: bad magic number".to_string());
+    }
+    if bytes.len() < HEADER_LEN {
+        return Err(format!(
+            "Truncated file: header needs {HEADER_LEN} bytes, found {}",
+            bytes.len()
+        ));
+    }
+    let version = u16_at(bytes, 4);
+    if version != VERSION {
+        return Err(format!("Unsupported binary graph version: {version}"));
+    }
+    if crc32(&bytes[..32]) != u32_at(bytes, 32) {
+        return Err("Corrupt file: header checksum mismatch".to_string());
+    }
+    let flags = u16_at(bytes, 6);
+    if flags & !(FLAG_UNDIRECTED | FLAG_LABELS) != 0 {
+        return Err(format!("Unknown flags: {flags:#06x}"));
+    }
+    let undirected = flags & FLAG_UNDIRECTED != 0;
+    let n = u64_at(bytes, 8);
+    let m = u64_at(bytes, 16);
+    let labels_len = u64_at(bytes, 24);
+    if n > u32::MAX as u64 {
+        return Err(format!("Too many nodes: {n}"));
+    }
+    if flags & FLAG_LABELS == 0 && labels_len != 0 {
+        return Err("Label section present but labels flag not set".to_string());
+    }
+    let expected_len = (n + 1)
+        .checked_mul(8)
+        .zip(m.checked_mul(12))
+        .and_then(|(offsets, edges)| offsets.checked_add(edges))
+        .and_then(|len| len.checked_add(labels_len))
+        .and_then(|len| len.checked_add((HEADER_LEN + TRAILER_LEN) as u64))
+        .ok_or("Corrupt file: section sizes overflow")?;
+    if (bytes.len() as u64) < expected_len {
+        return Err(format!(
+            "Truncated file: expected {expected_len} bytes, found {}",
+            bytes.len()
+        ));
+    }
+    if (bytes.len() as u64) > expected_len {
+        return Err(format!(
+            "Unexpected data after the end of the graph: expected {expected_len} bytes, found {}",
+            bytes.len()
+        ));
+    }
+    let (n, m, labels_len) = (n as usize, m as usize, labels_len as usize);
+    let body = &bytes[HEADER_LEN..bytes.len() - TRAILER_LEN];
+    if crc32(body) != u32_at(bytes, bytes.len() - TRAILER_LEN) {
+        return Err("Corrupt file: body checksum mismatch".to_string());
+    }
+
+    let (label_section, rest) = body.split_at(labels_len);
+    let (offsets, rest) = rest.split_at((n + 1) * 8);
+    let (targets, weights) = rest.split_at(m * 4);
+    let labels = if flags & FLAG_LABELS != 0 {
+        read_labels(label_section, n)?
+    } else {
+        vec![None; n]
+    };
+    let offsets: Vec<usize> = offsets
+        .chunks_exact(8)
+        .map(|c| u64::from_le_bytes(c.try_into().unwrap()) as usize)
+        .collect();
+    if offsets[0] != 0 || offsets[n] != m || offsets.windows(2).any(|w| w[0] > w[1]) {
+        return Err("Corrupt file: invalid edge offsets".to_string());
+    }
+    let targets: Vec<usize> = targets
+        .chunks_exact(4)
+        .map(|c| u32::from_le_bytes(c.try_into().unwrap()) as usize)
+        .collect();
+    let weights: Vec<f64> = weights
+        .chunks_exact(8)
+        .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+        .collect();
+
+    let mut degrees: Vec<usize> = offsets.windows(2).map(|w| w[1] - w[0]).collect();
+    for from in 0..n {
+        let row = &targets[offsets[from]..offsets[from + 1]];
+        if let Some(&to) = row.iter().find(|&&to| to >= n) {
+            return Err(format!("Corrupt file: edge {from} -> {to} out of range"));
+        }
+        if row.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(format!(
+                "Corrupt file: edges of node {from} are not strictly increasing"
+            ));
+        }
+        if undirected {
+            if let Some(&to) = row.iter().find(|&&to| to < from) {
+                return Err(format!(
+                    "Corrupt file: undirected edge {from} - {to} stored in the wrong row"
+                ));
+            }
+            for &to in row.iter().filter(|&&to| to != from) {
+                degrees[to] += 1;
+            }
+        }
+    }
+
+    let mut g = GraphList::with_nodes(undirected, 0);
+    for (label, degree) in labels.into_iter().zip(degrees) {
+        g.insert_node(label);
+        g.nodes.last_mut().unwrap().reserve_edges(degree);
+    }
+    for from in 0..n {
+        for k in offsets[from]..offsets[from + 1] {
+            g.nodes[from].add_edge(targets[k], weights[k]);
+            if undirected {
+                g.nodes[targets[k]].add_edge(from, weights[k]);
+            }
+        }
+    }
+    Ok(g)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_graph(undirected: bool) -> GraphList {
+        let mut graph = GraphList::with_nodes(undirected, 0);
+        graph.insert_node(Some("A".to_string()));
+        graph.insert_node(None);
+        graph.insert_node(Some("ñandú".to_string()));
+        graph.insert_node(Some(String::new()));
+        graph.insert_edge(0, 1, 1.5).unwrap();
+        graph.insert_edge(2, 0, -2.0).unwrap();
+        graph.insert_edge(2, 3, 0.0).unwrap();
+        graph.insert_edge(3, 3, 4.0).unwrap();
+        graph
+    }
+
+    fn assert_same_graph(a: &GraphList, b: &GraphList) {
+        assert_eq!(a.undirected, b.undirected);
+        assert_eq!(a.num_nodes(), b.num_nodes());
+        for i in 0..a.num_nodes() {
+            assert_eq!(a.nodes[i].label(), b.nodes[i].label());
+            assert_eq!(a.nodes[i].num_edges(), b.nodes[i].num_edges());
+            for j in 0..a.num_nodes() {
+                assert_eq!(a.get_edge(i, j).unwrap(), b.get_edge(i, j).unwrap());
+            }
+        }
+    }
+
+    // Recomputes both checksums after a test tampers with the contents.
+    fn reseal(bytes: &mut [u8]) {
+        let header_crc = crc32(&bytes[..32]);
+        bytes[32..36].copy_from_slice(&header_crc.to_le_bytes());
+        let end = bytes.len() - TRAILER_LEN;
+        let body_crc = crc32(&bytes[HEADER_LEN..end]);
+        bytes[end..].copy_from_slice(&body_crc.to_le_bytes());
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for undirected in [false, true] {
+            let graph = create_test_graph(undirected);
+            let read = read(&to_bytes(&graph)).unwrap();
+            assert_same_graph(&graph, &read);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_without_labels() {
+        let mut graph = GraphList::with_nodes(false, 3);
+        graph.insert_edge(0, 2, 1.0).unwrap();
+        let bytes = to_bytes(&graph);
+        assert_eq!(u16_at(&bytes, 6), 0);
+        assert_eq!(bytes.len(), HEADER_LEN + 4 * 8 + 12 + TRAILER_LEN);
+        assert_same_graph(&graph, &read(&bytes).unwrap());
+
+        let empty = GraphList::with_nodes(true, 0);
+        assert_same_graph(&empty, &read(&to_bytes(&empty)).unwrap());
+    }
+
+    #[test]
+    fn test_header_layout() {
+        let bytes = to_bytes(&create_test_graph(true));
+        assert_eq!(&bytes[..4], b"AG3G");
+        assert_eq!(u16_at(&bytes, 4), 1);
+        assert_eq!(u16_at(&bytes, 6), FLAG_UNDIRECTED | FLAG_LABELS);
+        assert_eq!(u64_at(&bytes, 8), 4);
+        // 0-1, 0-2, 2-3 and the 3-3 self-loop, each stored once.
+        assert_eq!(u64_at(&bytes, 16), 4);
+    }
+
+    #[test]
+    fn test_rejects_bad_header() {
+        let bytes = to_bytes(&create_test_graph(false));
+        assert!(read(b"").unwrap_err().contains("magic"));
+        assert!(read(b"GIF89a").unwrap_err().contains("magic"));
+        assert!(read(&bytes[..20]).unwrap_err().contains("Truncated"));
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] = 2;
+        assert!(read(&wrong_version).unwrap_err().contains("version"));
+
+        let mut corrupt_header = bytes.clone();
+        corrupt_header[8] ^= 1;
+        assert!(read(&corrupt_header)
+            .unwrap_err()
+            .contains("header checksum"));
+
+        let mut unknown_flags = bytes.clone();
+        unknown_flags[6] |= 0x80;
+        reseal(&mut unknown_flags);
+        assert!(read(&unknown_flags).unwrap_err().contains("flags"));
+    }
+
+    #[test]
+    fn test_rejects_truncated_and_padded_files() {
+        let bytes = to_bytes(&create_test_graph(false));
+        for len in [HEADER_LEN, bytes.len() / 2, bytes.len() - 1] {
+            assert!(read(&bytes[..len]).unwrap_err().contains("Truncated"));
+        }
+        let mut padded = bytes.clone();
+        padded.push(0);
+        assert!(read(&padded).unwrap_err().contains("Unexpected data"));
+
+        let mut huge = bytes.clone();
+        huge[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        reseal(&mut huge);
+        assert!(read(&huge).unwrap_err().contains("overflow"));
+    }
+
+    #[test]
+    fn test_rejects_corrupt_body() {
+        let bytes = to_bytes(&create_test_graph(false));
+        let mut flipped = bytes.clone();
+        flipped[HEADER_LEN + 1] ^= 0xFF;
+        assert!(read(&flipped).unwrap_err().contains("body checksum"));
+
+        // Point the first edge target past the last node.
+        let mut unlabeled = GraphList::with_nodes(false, 4);
+        unlabeled.insert_edge(0, 1, 1.0).unwrap();
+        let mut out_of_range = to_bytes(&unlabeled);
+        let targets_at = HEADER_LEN + 5 * 8;
+        out_of_range[targets_at..targets_at + 4].copy_from_slice(&9_u32.to_le_bytes());
+        reseal(&mut out_of_range);
+        assert!(read(&out_of_range).unwrap_err().contains("out of range"));
+    }
+}
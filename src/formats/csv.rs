@@ -0,0 +1,191 @@
+use crate::graph::GraphList;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// CSV edge lists, as exported by spreadsheets and Gephi: a header naming
+// the `source` and `target` columns (or `from` and `to`) and an optional
+// `weight` column, then one edge per row. Other columns are ignored.
+// Fields may be quoted, with `""` standing for a quote. Nodes are named in
+// the file and only exist through their edges.
+
+// Splits a row into its fields, or None for an unterminated quote.
+// Unquoted fields are trimmed.
+fn fields(line: &str) -> Option<Vec<String>> {
+    let mut fields = vec![];
+    let mut chars = line.chars().peekable();
+    loop {
+        let mut field = String::new();
+        let quoted = chars.peek() == Some(&'"');
+        if quoted {
+            chars.next();
+            loop {
+                match chars.next()? {
+                    '"' if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    '"' => break,
+                    c => field.push(c),
+                }
+            }
+        }
+        let mut rest = String::new();
+        let mut more = false;
+        for c in chars.by_ref() {
+            if c == ',' {
+                more = true;
+                break;
+            }
+            rest.push(c);
+        }
+        field.push_str(if quoted { rest.trim_end() } else { rest.trim() });
+        fields.push(field);
+        if !more {
+            return Some(fields);
+        }
+    }
+}
+
+fn quote(field: &str) -> String {
+    if field.contains([',', '"']) || field.trim() != field {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Positions of the source, target and weight columns in `header`.
+fn columns(header: &[String]) -> Option<(usize, usize, Option<usize>)> {
+    let position = |names: &[&str]| {
+        header
+            .iter()
+            .position(|h| names.contains(&h.to_lowercase().as_str()))
+    };
+    Some((
+        position(&["source", "from"])?,
+        position(&["target", "to"])?,
+        position(&["weight"]),
+    ))
+}
+
+/// Whether `line` is the header of a CSV edge list.
+pub(crate) fn is_header(line: &str) -> bool {
+    fields(line).is_some_and(|header| columns(&header).is_some())
+}
+
+/// Reads a CSV edge list as a directed graph. Nodes are numbered in order
+/// of first appearance and labelled with their names.
+pub fn read(input: &str) -> Result<GraphList, String> {
+    let mut rows = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let (i, header) = rows.next().ok_or("Missing CSV header")?;
+    let header = fields(header).ok_or(format!("Line {}: unterminated quote", i + 1))?;
+    let (source, target, weight) =
+        columns(&header).ok_or("CSV header needs `source` and `target` columns")?;
+    let mut g = GraphList::with_nodes(false, 0);
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    for (i, line) in rows {
+        let error = |message: &str| format!("Line {}: {message}", i + 1);
+        let row = fields(line).ok_or(error("unterminated quote"))?;
+        if row.len() != header.len() {
+            return Err(error(&format!("expected {} fields", header.len())));
+        }
+        let mut node = |name: &str| {
+            *indices.entry(name.to_string()).or_insert_with(|| {
+                g.insert_node(Some(name.to_string()));
+                g.num_nodes() - 1
+            })
+        };
+        let (from, to) = (node(&row[source]), node(&row[target]));
+        let weight = match weight.map(|w| &row[w]) {
+            Some(w) if !w.is_empty() => w
+                .parse()
+                .map_err(|_| error(&format!("invalid weight `{w}`")))?,
+            _ => 1.0,
+        };
+        g.insert_edge(from, to, weight)?;
+    }
+    Ok(g)
+}
+
+/// Writes `g` with `source`, `target` and `weight` columns, naming nodes by
+/// their label or, without one, their index. Undirected edges are written
+/// once.
+pub fn write(g: &GraphList) -> String {
+    let name = |i: usize| quote(&g.nodes[i].label().map_or(i.to_string(), str::to_string));
+    let mut out = String::from("source,target,weight\n");
+    for e in g.nodes.iter().flat_map(|node| node.get_ordered_edge_list()) {
+        if g.undirected && e.from > e.to {
+            continue;
+        }
+        writeln!(out, "{},{},{}", name(e.from), name(e.to), e.weight).unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    #[test]
+    fn test_fields() {
+        assert_eq!(fields("a, b ,c").unwrap(), ["a", "b", "c"]);
+        assert_eq!(fields("\" a \",b").unwrap(), [" a ", "b"]);
+        assert_eq!(fields("\"x, \"\"y\"\"\",,").unwrap(), ["x, \"y\"", "", ""]);
+        assert_eq!(fields("").unwrap(), [""]);
+        assert_eq!(fields("\"open"), None);
+    }
+
+    #[test]
+    fn test_read() {
+        let g = read("Source,Target,Type,Weight\nParis,Lyon,Directed,2.5\nLyon,Nice,Directed,\n\"Nice, FR\",Paris,Directed,1\n").unwrap();
+        assert!(!g.undirected);
+        let labels: Vec<_> = g.nodes.iter().map(|node| node.label().unwrap()).collect();
+        assert_eq!(labels, ["Paris", "Lyon", "Nice", "Nice, FR"]);
+        assert_eq!(g.get_edge(0, 1).unwrap().unwrap().weight, OrderedFloat(2.5));
+        assert_eq!(g.get_edge(1, 2).unwrap().unwrap().weight, OrderedFloat(1.0));
+        assert!(g.is_edge(3, 0));
+
+        let g = read("from,to\n1,2\n2,1\n").unwrap();
+        assert_eq!(g.make_edge_list().len(), 2);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("").is_err());
+        assert!(read("a,b\n1,2\n").is_err());
+        assert!(read("source,target\n1\n").is_err());
+        assert!(read("source,target\n\"1,2\n").is_err());
+        assert_eq!(
+            read("source,target,weight\n1,2,x\n").unwrap_err(),
+            "Line 2: invalid weight `x`"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut graph = GraphList::with_nodes(false, 0);
+        graph.insert_node(Some("a, \"b\"".to_string()));
+        graph.insert_node(None);
+        graph.insert_node(Some("c".to_string()));
+        graph.insert_edge(0, 1, 2.0).unwrap();
+        graph.insert_edge(1, 2, -0.5).unwrap();
+        graph.insert_edge(2, 2, 1.0).unwrap();
+        let written = write(&graph);
+        assert_eq!(
+            written,
+            "source,target,weight\n\"a, \"\"b\"\"\",1,2\n1,c,-0.5\nc,c,1\n"
+        );
+        let read = read(&written).unwrap();
+        assert_eq!(read.nodes[0].label(), graph.nodes[0].label());
+        assert_eq!(read.nodes[1].label(), Some("1"));
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_eq!(read.get_edge(i, j).unwrap(), graph.get_edge(i, j).unwrap());
+            }
+        }
+    }
+}
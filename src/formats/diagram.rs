@@ -0,0 +1,227 @@
+use crate::graph::{Edge, GraphList};
+use std::fmt::Write;
+
+// Exports to the text diagram languages understood by documentation tools:
+// Mermaid `flowchart`s and PlantUML. Nodes are named `n<index>` and shown
+// with their label, or their index when they have none. Edges show their
+// weight.
+
+/// Algorithm output to draw on top of the graph.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Highlight {
+    /// Consecutive nodes of a path, its nodes and edges are emphasized.
+    Path(Vec<usize>),
+    /// Component of each node, as returned by `dfs_connected_componentes`.
+    /// Each component gets its own fill color, `-1` is left uncolored.
+    Components(Vec<isize>),
+}
+
+pub(super) const PATH_COLOR: &str = "#d62728";
+pub(super) const PATH_FILL: &str = "#fde0dd";
+const PALETTE: [&str; 8] = [
+    "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462", "#b3de69", "#fccde5",
+];
+
+pub(super) fn component_color(component: isize) -> Option<&'static str> {
+    (component >= 0).then(|| PALETTE[component as usize % PALETTE.len()])
+}
+
+fn node_text(g: &GraphList, i: usize) -> String {
+    g.nodes[i]
+        .label()
+        .map_or_else(|| i.to_string(), str::to_string)
+}
+
+// Edges in drawing order. Undirected edges are drawn once.
+fn drawn_edges(g: &GraphList) -> Vec<&Edge> {
+    g.nodes
+        .iter()
+        .flat_map(|node| node.get_ordered_edge_list())
+        .filter(|e| !g.undirected || e.from <= e.to)
+        .collect()
+}
+
+fn on_path(g: &GraphList, highlight: Option<&Highlight>, e: &Edge) -> bool {
+    let Some(Highlight::Path(path)) = highlight else {
+        return false;
+    };
+    path.windows(2).any(|step| {
+        (step[0] == e.from && step[1] == e.to)
+            || (g.undirected && step[0] == e.to && step[1] == e.from)
+    })
+}
+
+fn in_path(highlight: Option<&Highlight>, i: usize) -> bool {
+    matches!(highlight, Some(Highlight::Path(path)) if path.contains(&i))
+}
+
+/// Writes `g` as a Mermaid left-to-right `flowchart`.
+pub fn write_mermaid(g: &GraphList, highlight: Option<&Highlight>) -> String {
+    let mut out = String::from("flowchart LR\n");
+    for node in &g.nodes {
+        let text = node_text(g, node.index()).replace('"', "#quot;");
+        writeln!(out, "    n{}[\"{text}\"]", node.index()).unwrap();
+    }
+    let arrow = if g.undirected { "---" } else { "-->" };
+    let mut path_links = vec![];
+    for (i, e) in drawn_edges(g).into_iter().enumerate() {
+        writeln!(out, "    n{} {arrow}|{}| n{}", e.from, e.weight, e.to).unwrap();
+        if on_path(g, highlight, e) {
+            path_links.push(i.to_string());
+        }
+    }
+    match highlight {
+        Some(Highlight::Path(_)) => {
+            let nodes: Vec<String> = (0..g.num_nodes())
+                .filter(|&i| in_path(highlight, i))
+                .map(|i| format!("n{i}"))
+                .collect();
+            if !nodes.is_empty() {
+                writeln!(
+                    out,
+                    "    classDef path fill:{PATH_FILL},stroke:{PATH_COLOR},stroke-width:3px"
+                )
+                .unwrap();
+                writeln!(out, "    class {} path", nodes.join(",")).unwrap();
+            }
+            if !path_links.is_empty() {
+                writeln!(
+                    out,
+                    "    linkStyle {} stroke:{PATH_COLOR},stroke-width:3px",
+                    path_links.join(",")
+                )
+                .unwrap();
+            }
+        }
+        Some(Highlight::Components(components)) => {
+            let mut ids: Vec<isize> = components.iter().copied().filter(|&c| c >= 0).collect();
+            ids.sort();
+            ids.dedup();
+            for c in ids {
+                let members: Vec<String> = (0..g.num_nodes())
+                    .filter(|&i| components.get(i) == Some(&c))
+                    .map(|i| format!("n{i}"))
+                    .collect();
+                if members.is_empty() {
+                    continue;
+                }
+                let color = component_color(c).unwrap();
+                writeln!(out, "    classDef c{c} fill:{color}").unwrap();
+                writeln!(out, "    class {} c{c}", members.join(",")).unwrap();
+            }
+        }
+        None => {}
+    }
+    out
+}
+
+/// Writes `g` as a PlantUML diagram of `agent`s.
+pub fn write_plantuml(g: &GraphList, highlight: Option<&Highlight>) -> String {
+    let mut out = String::from("@startuml\nleft to right direction\n");
+    for node in &g.nodes {
+        let i = node.index();
+        let text = node_text(g, i).replace('"', "'");
+        write!(out, "agent \"{text}\" as n{i}").unwrap();
+        let fill = match highlight {
+            Some(Highlight::Path(_)) if in_path(highlight, i) => Some(PATH_FILL),
+            Some(Highlight::Components(components)) => {
+                components.get(i).copied().and_then(component_color)
+            }
+            _ => None,
+        };
+        if let Some(fill) = fill {
+            write!(out, " {fill}").unwrap();
+        }
+        out.push('\n');
+    }
+    let head = if g.undirected { "" } else { ">" };
+    for e in drawn_edges(g) {
+        let style = if on_path(g, highlight, e) {
+            format!("[{PATH_COLOR},bold]")
+        } else {
+            String::new()
+        };
+        writeln!(out, "n{} -{style}-{head} n{} : {}", e.from, e.to, e.weight).unwrap();
+    }
+    out.push_str("@enduml\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_graph(undirected: bool) -> GraphList {
+        let mut graph = GraphList::with_nodes(undirected, 0);
+        graph.insert_node(Some("Start".to_string()));
+        graph.insert_node(None);
+        graph.insert_node(Some("say \"end\"".to_string()));
+        graph.insert_edge(0, 1, 2.5).unwrap();
+        graph.insert_edge(1, 2, 1.0).unwrap();
+        graph.insert_edge(0, 2, 7.0).unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_mermaid() {
+        let out = write_mermaid(&create_test_graph(false), None);
+        assert_eq!(
+            out,
+            "flowchart LR
+    n0[\"Start\"]
+    n1[\"1\"]
+    n2[\"say #quot;end#quot;\"]
+    n0 -->|2.5| n1
+    n0 -->|7| n2
+    n1 -->|1| n2
+"
+        );
+    }
+
+    #[test]
+    fn test_mermaid_path() {
+        let highlight = Highlight::Path(vec![2, 1, 0]);
+        let out = write_mermaid(&create_test_graph(true), Some(&highlight));
+        assert!(out.contains("    n0 ---|2.5| n1\n"));
+        assert!(out.contains("    class n0,n1,n2 path\n"));
+        // Links 0 (0-1) and 2 (1-2), walked backwards.
+        assert!(out.contains("    linkStyle 0,2 stroke:#d62728,stroke-width:3px\n"));
+    }
+
+    #[test]
+    fn test_mermaid_components() {
+        let highlight = Highlight::Components(vec![0, 1, 0]);
+        let out = write_mermaid(&create_test_graph(false), Some(&highlight));
+        assert!(out.contains("    classDef c0 fill:#8dd3c7\n    class n0,n2 c0\n"));
+        assert!(out.contains("    classDef c1 fill:#ffffb3\n    class n1 c1\n"));
+    }
+
+    #[test]
+    fn test_plantuml() {
+        let highlight = Highlight::Path(vec![0, 1]);
+        let out = write_plantuml(&create_test_graph(false), Some(&highlight));
+        assert_eq!(
+            out,
+            "@startuml
+left to right direction
+agent \"Start\" as n0 #fde0dd
+agent \"1\" as n1 #fde0dd
+agent \"say 'end'\" as n2
+n0 -[#d62728,bold]-> n1 : 2.5
+n0 --> n2 : 7
+n1 --> n2 : 1
+@enduml
+"
+        );
+    }
+
+    #[test]
+    fn test_plantuml_undirected_components() {
+        let highlight = Highlight::Components(vec![0, 0, -1]);
+        let out = write_plantuml(&create_test_graph(true), Some(&highlight));
+        assert!(out.contains("agent \"1\" as n1 #8dd3c7\n"));
+        assert!(out.contains("agent \"say 'end'\" as n2\n"));
+        assert!(out.contains("n1 -- n2 : 1\n"));
+        assert!(!out.contains("->"));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::graph::GraphList;
+use std::fmt::Write;
+
+// DIMACS graphs: `c` comment lines, a problem line `p <problem> n m` and
+// `m` edge lines with 1-based node ids. Shortest path files (`p sp`) list
+// weighted arcs `a u v w`; coloring and clique files (`p edge` or `p col`)
+// list undirected edges `e u v`.
+
+/// Reads a shortest path (`p sp`) file as a directed graph, or an `edge` /
+/// `col` file as an undirected one.
+pub fn read(input: &str) -> Result<GraphList, String> {
+    let mut graph = None;
+    let mut declared = 0;
+    let mut found = 0;
+    for (i, line) in input.lines().enumerate() {
+        let error = |message: String| format!("Line {}: {message}", i + 1);
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let parse = |token: &str| -> Result<usize, String> {
+            token
+                .parse()
+                .map_err(|_| error(format!("invalid number `{token}`")))
+        };
+        match tokens.first().copied() {
+            None | Some("c") => {}
+            Some("p") => {
+                if graph.is_some() {
+                    return Err(error("second problem line".to_string()));
+                }
+                let [_, problem, n, m] = tokens[..] else {
+                    return Err(error("expected `p <problem> n m`".to_string()));
+                };
+                let undirected = match problem {
+                    "sp" => false,
+                    "edge" | "col" => true,
+                    _ => return Err(error(format!("unsupported problem `{problem}`"))),
+                };
+                declared = parse(m)?;
+                graph = Some(GraphList::try_with_nodes(undirected, parse(n)?)?);
+            }
+            Some(kind @ ("a" | "e")) => {
+                let g = graph
+                    .as_mut()
+                    .ok_or(error("edge before the problem line".to_string()))?;
+                let expected = if g.undirected { "e" } else { "a" };
+                if kind != expected {
+                    return Err(error(format!("expected `{expected}` lines")));
+                }
+                let columns = if g.undirected { 3 } else { 4 };
+                if tokens.len() != columns {
+                    return Err(error(format!("expected {columns} columns")));
+                }
+                let node = |token: &str| match parse(token)? {
+                    id @ 1.. if id <= g.num_nodes() => Ok(id - 1),
+                    _ => Err(error(format!("node out of range: {token}"))),
+                };
+                let (from, to) = (node(tokens[1])?, node(tokens[2])?);
+                let weight = match tokens.get(3) {
+                    Some(w) => w
+                        .parse()
+                        .map_err(|_| error(format!("invalid weight `{w}`")))?,
+                    None => 1.0,
+                };
+                g.insert_edge(from, to, weight)?;
+                found += 1;
+            }
+            Some(other) => return Err(error(format!("unknown line type `{other}`"))),
+        }
+    }
+    let graph = graph.ok_or("Missing problem line")?;
+    if found != declared {
+        return Err(format!("Expected {declared} edges, found {found}"));
+    }
+    Ok(graph)
+}
+
+/// Writes a directed graph as a shortest path file and an undirected one as
+/// an `edge` file, which has no weights.
+pub fn write(g: &GraphList) -> String {
+    let edges: Vec<_> = g
+        .nodes
+        .iter()
+        .flat_map(|node| node.get_ordered_edge_list())
+        .filter(|e| !g.undirected || e.from <= e.to)
+        .collect();
+    let problem = if g.undirected { "edge" } else { "sp" };
+    let mut out = format!("p {problem} {} {}\n", g.num_nodes(), edges.len());
+    for e in edges {
+        if g.undirected {
+            writeln!(out, "e {} {}", e.from + 1, e.to + 1).unwrap();
+        } else {
+            writeln!(out, "a {} {} {}", e.from + 1, e.to + 1, e.weight).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    #[test]
+    fn test_read() {
+        let g = read("c 9th DIMACS challenge\np sp 3 2\na 1 2 5\nc middle\na 3 1 -2\n").unwrap();
+        assert!(!g.undirected);
+        assert_eq!(g.num_nodes(), 3);
+        assert_eq!(g.get_edge(0, 1).unwrap().unwrap().weight, OrderedFloat(5.0));
+        assert_eq!(
+            g.get_edge(2, 0).unwrap().unwrap().weight,
+            OrderedFloat(-2.0)
+        );
+
+        let g = read("p edge 4 2\ne 1 2\ne 4 3\n").unwrap();
+        assert!(g.undirected);
+        assert!(g.is_edge(1, 0) && g.is_edge(2, 3));
+        assert_eq!(g.make_edge_list().len(), 4);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("").is_err());
+        assert!(read("a 1 2 1\np sp 2 1\n").is_err());
+        assert!(read("p max 2 1\n").is_err());
+        assert!(read("p sp 2\n").is_err());
+        assert!(read("p sp 2 1\na 1 3 1\n").is_err());
+        assert!(read("p sp 2 1\ne 1 2\n").is_err());
+        assert!(read("p sp 2 1\na 1 2\n").is_err());
+        assert!(read("p sp 2 2\na 1 2 1\n").is_err());
+        assert!(read("p sp 2 0\np sp 2 0\n").is_err());
+        assert_eq!(
+            read("p sp -1 0\n").unwrap_err(),
+            "Line 1: invalid number `-1`"
+        );
+        assert_eq!(
+            read("p sp 18446744073709551615 0\n").unwrap_err(),
+            "Not enough memory for 18446744073709551615 nodes"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for undirected in [false, true] {
+            let mut graph = GraphList::with_nodes(undirected, 4);
+            graph.insert_edge(0, 1, 1.0).unwrap();
+            graph.insert_edge(2, 0, 1.0).unwrap();
+            graph.insert_edge(1, 1, 1.0).unwrap();
+            let read = read(&write(&graph)).unwrap();
+            assert_eq!(read.undirected, undirected);
+            assert_eq!(read.num_nodes(), 4);
+            for i in 0..4 {
+                for j in 0..4 {
+                    assert_eq!(read.get_edge(i, j).unwrap(), graph.get_edge(i, j).unwrap());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use crate::graph::GraphList;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// Graphviz DOT (https://graphviz.org/doc/info/lang.html). Node and edge
+// statements, including chains like `a -> b -> c`, are read; the `label`
+// of nodes and the `weight` of edges are kept, ports and all other
+// attributes ignored. Subgraphs are not supported.
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// An identifier, keyword or numeral.
+    Word(String),
+    /// A double-quoted or HTML string.
+    Quoted(String),
+    Punct(char),
+    /// `--` or `->`.
+    Edge(&'static str),
+}
+
+// Tokens with their line numbers.
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let (mut i, mut line) = (0, 1);
+    let mut line_start = true;
+    let error = |line: usize, message: &str| format!("DOT line {line}: {message}");
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == '\n' {
+            line += 1;
+            line_start = true;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token_line = line;
+        if (c == '#' && line_start) || (c == '/' && next == Some('/')) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        line_start = false;
+        if c == '/' && next == Some('*') {
+            i += 2;
+            while !(chars.get(i) == Some(&'*') && chars.get(i + 1) == Some(&'/')) {
+                match chars.get(i) {
+                    Some('\n') => line += 1,
+                    Some(_) => {}
+                    None => return Err(error(token_line, "unterminated comment")),
+                }
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+        let token = match c {
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' | ':' => {
+                i += 1;
+                Token::Punct(c)
+            }
+            '-' if next == Some('-') || next == Some('>') => {
+                i += 2;
+                Token::Edge(if next == Some('-') { "--" } else { "->" })
+            }
+            '"' => {
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match (chars.get(i), chars.get(i + 1)) {
+                        (None, _) => return Err(error(token_line, "unterminated string")),
+                        (Some('"'), _) => break,
+                        (Some('\\'), Some(&escaped @ ('"' | '\\'))) => {
+                            text.push(escaped);
+                            i += 1;
+                        }
+                        (Some('\\'), Some('\n')) => {
+                            line += 1;
+                            i += 1;
+                        }
+                        (Some(&c), _) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            text.push(c);
+                        }
+                    }
+                    i += 1;
+                }
+                i += 1;
+                Token::Quoted(text)
+            }
+            '<' => {
+                let mut depth = 0;
+                let start = i + 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(error(token_line, "unterminated HTML string")),
+                        Some('<') => depth += 1,
+                        Some('>') => depth -= 1,
+                        Some('\n') => line += 1,
+                        Some(_) => {}
+                    }
+                    i += 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                Token::Quoted(chars[start..i - 1].iter().collect())
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let start = i;
+                i += 1;
+                while chars
+                    .get(i)
+                    .is_some_and(|&c| c.is_alphanumeric() || c == '_' || c == '.')
+                {
+                    i += 1;
+                }
+                Token::Word(chars[start..i].iter().collect())
+            }
+            c => return Err(error(token_line, &format!("unexpected `{c}`"))),
+        };
+        tokens.push((token_line, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+    fn error(&self, message: &str) -> String {
+        let line = self
+            .tokens
+            .get(self.position)
+            .or(self.tokens.last())
+            .map_or(1, |&(line, _)| line);
+        format!("DOT line {line}: {message}")
+    }
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+    // Consumes `c` if it is next.
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(&Token::Punct(c));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{c}`")))
+        }
+    }
+    fn id(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Word(id) | Token::Quoted(id)) => {
+                let id = id.clone();
+                self.position += 1;
+                Ok(id)
+            }
+            _ => Err(self.error("expected an id")),
+        }
+    }
+    // A node id, skipping its port and compass point.
+    fn node_id(&mut self) -> Result<String, String> {
+        let id = self.id()?;
+        for _ in 0..2 {
+            if self.eat(':') {
+                self.id()?;
+            }
+        }
+        Ok(id)
+    }
+    // Any number of `[name = value, ...]` lists.
+    fn attributes(&mut self) -> Result<HashMap<String, String>, String> {
+        let mut attributes = HashMap::new();
+        while self.eat('[') {
+            while !self.eat(']') {
+                let name = self.id()?;
+                self.expect('=')?;
+                attributes.insert(name, self.id()?);
+                if !self.eat(',') {
+                    self.eat(';');
+                }
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+/// Reads the graph of a DOT file. Nodes are numbered in order of first
+/// appearance.
+pub fn read(input: &str) -> Result<GraphList, String> {
+    let mut p = Parser {
+        tokens: tokenize(input)?,
+        position: 0,
+    };
+    if p.is_keyword("strict") {
+        p.next();
+    }
+    let directed = if p.is_keyword("digraph") {
+        true
+    } else if p.is_keyword("graph") {
+        false
+    } else {
+        return Err(p.error("expected `graph` or `digraph`"));
+    };
+    p.next();
+    if !p.eat('{') {
+        p.id()?;
+        p.expect('{')?;
+    }
+    let edge_op = if directed { "->" } else { "--" };
+    let mut g = GraphList::with_nodes(!directed, 0);
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    let mut index = |g: &mut GraphList, id: String| {
+        *indices
+            .entry(id)
+            .or_insert_with(|| g.insert_node(None).index())
+    };
+    loop {
+        if p.eat('}') {
+            break;
+        }
+        if p.eat(';') {
+            continue;
+        }
+        if p.peek().is_none() {
+            return Err(p.error("missing `}`"));
+        }
+        if p.is_keyword("subgraph") || p.peek() == Some(&Token::Punct('{')) {
+            return Err(p.error("subgraphs are not supported"));
+        }
+        if ["graph", "node", "edge"].iter().any(|k| p.is_keyword(k)) {
+            p.next();
+            p.attributes()?;
+            continue;
+        }
+        let first = p.node_id()?;
+        if p.eat('=') {
+            // A graph attribute.
+            p.id()?;
+            continue;
+        }
+        let mut chain = vec![first];
+        while let Some(Token::Edge(op)) = p.peek() {
+            if *op != edge_op {
+                return Err(p.error(&format!(
+                    "`{op}` in a {}",
+                    if directed { "digraph" } else { "graph" }
+                )));
+            }
+            p.next();
+            chain.push(p.node_id()?);
+        }
+        let attributes = p.attributes()?;
+        let nodes: Vec<usize> = chain.into_iter().map(|id| index(&mut g, id)).collect();
+        if let [node] = nodes[..] {
+            if let Some(label) = attributes.get("label") {
+                g.nodes[node].label = Some(label.clone());
+            }
+            continue;
+        }
+        let weight = match attributes.get("weight") {
+            Some(w) => w
+                .parse()
+                .map_err(|_| p.error(&format!("invalid weight `{w}`")))?,
+            None => 1.0,
+        };
+        for pair in nodes.windows(2) {
+            g.insert_edge(pair[0], pair[1], weight)?;
+        }
+    }
+    if p.peek().is_some() {
+        return Err(p.error("unexpected text after the graph"));
+    }
+    Ok(g)
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Writes `g` with nodes named by their index, labels as `label` and
+/// weights as `weight` attributes.
+pub fn write(g: &GraphList) -> String {
+    let (kind, op) = if g.undirected {
+        ("graph", "--")
+    } else {
+        ("digraph", "->")
+    };
+    let mut out = format!("{kind} {{\n");
+    for node in &g.nodes {
+        match node.label() {
+            Some(label) => writeln!(out, "  {} [label={}];", node.index(), quote(label)).unwrap(),
+            None => writeln!(out, "  {};", node.index()).unwrap(),
+        }
+    }
+    for node in &g.nodes {
+        for e in node.get_ordered_edge_list() {
+            if g.undirected && e.from > e.to {
+                continue;
+            }
+            writeln!(out, "  {} {op} {} [weight={}];", e.from, e.to, e.weight).unwrap();
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    fn weight(g: &GraphList, from: usize, to: usize) -> Option<OrderedFloat<f64>> {
+        g.get_edge(from, to).unwrap().map(|e| e.weight)
+    }
+
+    #[test]
+    fn test_read() {
+        let input = r#"/* A small
+   network */
+strict digraph "G" {
+  rankdir = LR; node [shape=box]
+  // a comment
+  a [label="First \"node\"", color=red]
+  a -> b -> c [weight=2.5]
+  c:e -> a:w;
+  d
+# a preprocessor line
+  e [label=<<b>bold</b>>]
+}
+"#;
+        let g = read(input).unwrap();
+        assert!(!g.undirected);
+        assert_eq!(g.num_nodes(), 5);
+        assert_eq!(g.nodes[0].label(), Some("First \"node\""));
+        assert_eq!(g.nodes[1].label(), None);
+        assert_eq!(g.nodes[4].label(), Some("<b>bold</b>"));
+        assert_eq!(weight(&g, 0, 1), Some(OrderedFloat(2.5)));
+        assert_eq!(weight(&g, 1, 2), Some(OrderedFloat(2.5)));
+        assert_eq!(weight(&g, 2, 0), Some(OrderedFloat(1.0)));
+        assert_eq!(weight(&g, 1, 0), None);
+
+        let g = read("graph { 1 -- 2 [weight=-3] }").unwrap();
+        assert!(g.undirected);
+        assert_eq!(weight(&g, 1, 0), Some(OrderedFloat(-3.0)));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("").is_err());
+        assert!(read("graph [ id 0 ]").is_err());
+        assert!(read("digraph { a -> b").is_err());
+        assert_eq!(
+            read("graph {\n a -> b\n}").unwrap_err(),
+            "DOT line 2: `->` in a graph"
+        );
+        assert!(read("digraph { subgraph s { a } }").is_err());
+        assert!(read("digraph { a -> b [weight=x] }").is_err());
+        assert!(read("digraph { a [label=\"open] }").is_err());
+        assert!(read("digraph { a } b").is_err());
+        assert!(read("digraph { /* open").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for undirected in [false, true] {
+            let mut graph = GraphList::with_nodes(undirected, 0);
+            graph.insert_node(Some("say \"hi\" \\ bye".to_string()));
+            graph.insert_node(None);
+            graph.insert_node(Some("C".to_string()));
+            graph.insert_edge(0, 1, 2.0).unwrap();
+            graph.insert_edge(2, 0, -0.5).unwrap();
+            graph.insert_edge(1, 1, 1.0).unwrap();
+            let read = read(&write(&graph)).unwrap();
+            assert_eq!(read.undirected, undirected);
+            for i in 0..3 {
+                assert_eq!(read.nodes[i].label(), graph.nodes[i].label());
+                for j in 0..3 {
+                    assert_eq!(read.get_edge(i, j).unwrap(), graph.get_edge(i, j).unwrap());
+                }
+            }
+        }
+    }
+}
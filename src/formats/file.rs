@@ -0,0 +1,620 @@
+use super::gexf::GexfGraph;
+use super::json::NodeAnnotations;
+use super::metis::MetisGraph;
+use super::plain::{PlainFormat, PlainOptions};
+use super::svg::SvgStyle;
+use super::{
+    binary, csv, diagram, dimacs, dot, gexf, gml, graphml, json, leda, matrix_market, metis, pajek,
+    plain, snap, svg, tsplib,
+};
+use crate::graph::GraphList;
+use crate::layout::force_directed_layout;
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::Path;
+
+// Loading and saving graphs without naming the format. The format of a file
+// is recognized by, in order:
+//
+// 1. a marker in its content, such as the binary magic, `%%MatrixMarket`,
+//    `<gexf`, `digraph {` or `LEDA.GRAPH`;
+// 2. its extension, ignoring a trailing `.gz` or `.zst`;
+// 3. for files of plain numbers, the first of the plain edge list, plain
+//    matrix, METIS and SNAP readers that accepts it.
+//
+// Gzip and zstd compressed files are recognized by their magic bytes.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Binary,
+    MatrixMarket,
+    NodeLinkJson,
+    AdjacencyJson,
+    Gexf,
+    GraphMl,
+    Gml,
+    Dot,
+    Pajek,
+    Leda,
+    Metis,
+    Snap,
+    /// Read only.
+    Tsplib,
+    /// Plain `n m` edge list, see `plain`.
+    EdgeList,
+    /// Plain weight matrix, see `plain`.
+    Matrix,
+    /// Edge list with a `source,target` header.
+    Csv,
+    /// DIMACS shortest path or coloring file.
+    Dimacs,
+    /// Write only.
+    Mermaid,
+    /// Write only.
+    PlantUml,
+    /// Write only, drawn with a force-directed layout.
+    Svg,
+}
+
+const EXTENSIONS: [(&str, Format); 31] = [
+    ("ag3", Format::Binary),
+    ("bin", Format::Binary),
+    ("mtx", Format::MatrixMarket),
+    ("mm", Format::MatrixMarket),
+    ("json", Format::NodeLinkJson),
+    ("gexf", Format::Gexf),
+    ("graphml", Format::GraphMl),
+    ("gml", Format::Gml),
+    ("dot", Format::Dot),
+    ("gv", Format::Dot),
+    ("net", Format::Pajek),
+    ("paj", Format::Pajek),
+    ("gw", Format::Leda),
+    ("lgr", Format::Leda),
+    ("graph", Format::Metis),
+    ("metis", Format::Metis),
+    ("snap", Format::Snap),
+    ("tsp", Format::Tsplib),
+    ("atsp", Format::Tsplib),
+    ("edges", Format::EdgeList),
+    ("el", Format::EdgeList),
+    ("adj", Format::Matrix),
+    ("csv", Format::Csv),
+    ("gr", Format::Dimacs),
+    ("col", Format::Dimacs),
+    ("dimacs", Format::Dimacs),
+    ("mmd", Format::Mermaid),
+    ("mermaid", Format::Mermaid),
+    ("puml", Format::PlantUml),
+    ("plantuml", Format::PlantUml),
+    ("svg", Format::Svg),
+];
+
+const NAMES: [(&str, Format); 20] = [
+    ("binary", Format::Binary),
+    ("matrix-market", Format::MatrixMarket),
+    ("node-link", Format::NodeLinkJson),
+    ("adjacency", Format::AdjacencyJson),
+    ("gexf", Format::Gexf),
+    ("graphml", Format::GraphMl),
+    ("gml", Format::Gml),
+    ("dot", Format::Dot),
+    ("pajek", Format::Pajek),
+    ("leda", Format::Leda),
+    ("metis", Format::Metis),
+    ("snap", Format::Snap),
+    ("tsplib", Format::Tsplib),
+    ("edge-list", Format::EdgeList),
+    ("matrix", Format::Matrix),
+    ("csv", Format::Csv),
+    ("dimacs", Format::Dimacs),
+    ("mermaid", Format::Mermaid),
+    ("plantuml", Format::PlantUml),
+    ("svg", Format::Svg),
+];
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Format::Binary => "binary",
+            Format::MatrixMarket => "Matrix Market",
+            Format::NodeLinkJson => "node-link JSON",
+            Format::AdjacencyJson => "adjacency JSON",
+            Format::Gexf => "GEXF",
+            Format::GraphMl => "GraphML",
+            Format::Gml => "GML",
+            Format::Dot => "DOT",
+            Format::Pajek => "Pajek",
+            Format::Leda => "LEDA",
+            Format::Metis => "METIS",
+            Format::Snap => "SNAP",
+            Format::Tsplib => "TSPLIB",
+            Format::EdgeList => "edge list",
+            Format::Matrix => "matrix",
+            Format::Csv => "CSV",
+            Format::Dimacs => "DIMACS",
+            Format::Mermaid => "Mermaid",
+            Format::PlantUml => "PlantUML",
+            Format::Svg => "SVG",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+impl Compression {
+    pub fn from_extension(path: &Path) -> Compression {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst" | "zstd") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+    pub fn detect(bytes: &[u8]) -> Compression {
+        if bytes.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+    fn compress(self, bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+        let error = |e: std::io::Error| format!("Compression failed: {e}");
+        match self {
+            Compression::None => Ok(bytes),
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&bytes).map_err(error)?;
+                encoder.finish().map_err(error)
+            }
+            Compression::Zstd => zstd::encode_all(bytes.as_slice(), 0).map_err(error),
+        }
+    }
+    fn decompress(self, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        let error = |e: std::io::Error| format!("Decompression failed: {e}");
+        match self {
+            Compression::None => Ok(bytes.to_vec()),
+            Compression::Gzip => {
+                let mut out = vec![];
+                flate2::read::MultiGzDecoder::new(bytes)
+                    .read_to_end(&mut out)
+                    .map_err(error)?;
+                Ok(out)
+            }
+            Compression::Zstd => zstd::decode_all(bytes).map_err(error),
+        }
+    }
+}
+
+impl Format {
+    /// The format named by the extension of `path`, looking past a `.gz` or
+    /// `.zst` extension.
+    pub fn from_extension(path: &Path) -> Option<Format> {
+        let path = match Compression::from_extension(path) {
+            Compression::None => path,
+            _ => Path::new(path.file_stem()?),
+        };
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        EXTENSIONS
+            .iter()
+            .find(|(e, _)| *e == extension)
+            .map(|&(_, format)| format)
+    }
+
+    /// The format called `name`, such as `pajek` or `edge-list`, or using
+    /// `name` as extension.
+    pub fn from_name(name: &str) -> Option<Format> {
+        let name = name.to_lowercase();
+        NAMES
+            .iter()
+            .chain(&EXTENSIONS)
+            .find(|(n, _)| *n == name)
+            .map(|&(_, format)| format)
+    }
+
+    /// Recognizes the formats that mark their content. Plain numeric formats
+    /// are left to `decode`.
+    pub fn sniff(bytes: &[u8]) -> Option<Format> {
+        if bytes.starts_with(b"AG3G") {
+            return Some(Format::Binary);
+        }
+        let text = std::str::from_utf8(bytes).ok()?.trim_start();
+        if text.starts_with("%%MatrixMarket") {
+            return Some(Format::MatrixMarket);
+        }
+        if text.starts_with('{') {
+            let document: serde_json::Value = serde_json::from_str(text).ok()?;
+            return if document.get("adjacency").is_some() {
+                Some(Format::AdjacencyJson)
+            } else {
+                Some(Format::NodeLinkJson)
+            };
+        }
+        if text.starts_with('<') {
+            return if text.contains("<gexf") {
+                Some(Format::Gexf)
+            } else if text.contains("<graphml") {
+                Some(Format::GraphMl)
+            } else {
+                None
+            };
+        }
+        let first = |comment: char| {
+            text.lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty() && !line.starts_with(comment))
+                .unwrap_or("")
+        };
+        if first('#').starts_with("LEDA.GRAPH") {
+            return Some(Format::Leda);
+        }
+        let pajek = first('%').to_lowercase();
+        if pajek.starts_with("*vertices") || pajek.starts_with("*network") {
+            return Some(Format::Pajek);
+        }
+        let dimacs: Vec<&str> = first('c').split_whitespace().collect();
+        if dimacs.len() == 4 && dimacs[0] == "p" {
+            return Some(Format::Dimacs);
+        }
+        if csv::is_header(first('#')) {
+            return Some(Format::Csv);
+        }
+        let tokens: Vec<&str> = text
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(str::split_whitespace)
+            .take(64)
+            .collect();
+        if tokens
+            .windows(2)
+            .any(|pair| pair[0] == "graph" && pair[1].starts_with('['))
+        {
+            return Some(Format::Gml);
+        }
+        let mut dot = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#') && !line.starts_with("//"))
+            .flat_map(str::split_whitespace)
+            .map(str::to_lowercase)
+            .skip_while(|token| token == "strict");
+        if dot
+            .next()
+            .is_some_and(|token| token == "graph" || token == "digraph")
+            && dot.take(2).any(|token| token.contains('{'))
+        {
+            return Some(Format::Dot);
+        }
+        let keys: Vec<&str> = text
+            .lines()
+            .take(16)
+            .filter_map(|line| Some(line.split_once(':')?.0.trim()))
+            .collect();
+        if keys.contains(&"TYPE") && keys.contains(&"DIMENSION") {
+            return Some(Format::Tsplib);
+        }
+        None
+    }
+}
+
+// Plain options matching an edge list or matrix, or None if `text` is
+// neither.
+fn plain_options(text: &str) -> Option<PlainOptions> {
+    let rows: Vec<Vec<&str>> = text
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .filter(|tokens| !tokens.is_empty())
+        .collect();
+    let (header, data) = rows.split_first()?;
+    let numbers =
+        |row: &[&str]| -> Option<Vec<f64>> { row.iter().map(|token| token.parse().ok()).collect() };
+    match header.as_slice() {
+        [n] => {
+            let n: usize = n.parse().ok()?;
+            let matrix = data
+                .iter()
+                .map(|row| numbers(row))
+                .collect::<Option<Vec<_>>>()?;
+            if matrix.len() != n || matrix.iter().any(|row| row.len() != n) {
+                return None;
+            }
+            Some(PlainOptions {
+                format: PlainFormat::Matrix,
+                one_based: false,
+                weighted: matrix.iter().flatten().any(|&w| w != 0.0 && w != 1.0),
+                undirected: (0..n).all(|i| (0..i).all(|j| matrix[i][j] == matrix[j][i])),
+            })
+        }
+        [n, m] => {
+            let n: usize = n.parse().ok()?;
+            let m: usize = m.parse().ok()?;
+            let columns = data.first().map_or(2, Vec::len);
+            if data.len() != m || data.iter().any(|row| row.len() != columns) {
+                return None;
+            }
+            let ids = data
+                .iter()
+                .flat_map(|row| &row[..2])
+                .map(|id| id.parse::<usize>().ok())
+                .collect::<Option<Vec<_>>>()?;
+            let (min, max) = (ids.iter().min(), ids.iter().max());
+            Some(PlainOptions {
+                format: PlainFormat::EdgeList,
+                one_based: min.is_some_and(|&min| min >= 1) && max.is_some_and(|&max| max <= n),
+                weighted: columns == 3,
+                undirected: false,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn read_text(text: &str, format: Format) -> Result<GraphList, String> {
+    match format {
+        Format::Binary => unreachable!("binary files are not text"),
+        Format::MatrixMarket => matrix_market::read_graph_list(text),
+        Format::NodeLinkJson => Ok(json::read_node_link(text)?.0),
+        Format::AdjacencyJson => Ok(json::read_adjacency(text)?.0),
+        Format::Gexf => Ok(gexf::read(text)?.graph),
+        Format::GraphMl => graphml::read(text),
+        Format::Gml => gml::read(text),
+        Format::Dot => dot::read(text),
+        Format::Pajek => pajek::read(text),
+        Format::Leda => leda::read(text),
+        Format::Metis => Ok(metis::read(text)?.graph),
+        Format::Snap => Ok(snap::read(text)?.graph),
+        Format::Tsplib => Ok(tsplib::read(text)?.to_graph_list()),
+        Format::EdgeList | Format::Matrix => {
+            let options = plain_options(text)
+                .filter(|options| {
+                    (options.format == PlainFormat::EdgeList) == (format == Format::EdgeList)
+                })
+                .ok_or(format!("Not a plain {format}"))?;
+            plain::read_graph_list(text, &options)
+        }
+        Format::Csv => csv::read(text),
+        Format::Dimacs => dimacs::read(text),
+        Format::Mermaid | Format::PlantUml | Format::Svg => {
+            Err(format!("{format} files cannot be read"))
+        }
+    }
+}
+
+/// Reads a graph from the (possibly compressed) bytes of a file. `hint` is
+/// the format to use when the content has no marker, usually taken from
+/// the file extension. Returns the graph and the format it was read as.
+pub fn decode(bytes: &[u8], hint: Option<Format>) -> Result<(GraphList, Format), String> {
+    let bytes = Compression::detect(bytes).decompress(bytes)?;
+    if let Some(format) = Format::sniff(&bytes).or(hint) {
+        if format == Format::Binary {
+            return Ok((binary::read(&bytes)?, format));
+        }
+        let text =
+            std::str::from_utf8(&bytes).map_err(|_| format!("{format} file is not UTF-8"))?;
+        return Ok((read_text(text, format)?, format));
+    }
+    let text = std::str::from_utf8(&bytes).map_err(|_| "Unrecognized binary file".to_string())?;
+    for format in [
+        Format::EdgeList,
+        Format::Matrix,
+        Format::Metis,
+        Format::Snap,
+    ] {
+        if let Ok(g) = read_text(text, format) {
+            return Ok((g, format));
+        }
+    }
+    Err("Unrecognized graph format".to_string())
+}
+
+/// Serializes `g` as `format`, then compresses it.
+pub fn encode(g: &GraphList, format: Format, compression: Compression) -> Result<Vec<u8>, String> {
+    let plain_options = |format| PlainOptions {
+        format,
+        weighted: true,
+        ..Default::default()
+    };
+    let text = match format {
+        Format::Binary => return compression.compress(binary::to_bytes(g)),
+        Format::MatrixMarket => matrix_market::write_graph_list(g),
+        Format::NodeLinkJson => json::write_node_link(g, &NodeAnnotations::default()),
+        Format::AdjacencyJson => json::write_adjacency(g, &NodeAnnotations::default()),
+        Format::Gexf => gexf::write(&GexfGraph::from(g.clone())),
+        Format::GraphMl => graphml::write(g),
+        Format::Gml => gml::write(g)?,
+        Format::Dot => dot::write(g),
+        Format::Pajek => pajek::write(g),
+        Format::Leda => leda::write(g)?,
+        Format::Metis => metis::write(&MetisGraph::from(g.clone()))?,
+        Format::Snap => snap::write(g, None)?,
+        Format::Tsplib => return Err("TSPLIB files cannot be written".to_string()),
+        Format::EdgeList => plain::write_graph_list(g, &plain_options(PlainFormat::EdgeList))?,
+        Format::Matrix => plain::write_graph_list(g, &plain_options(PlainFormat::Matrix))?,
+        Format::Csv => csv::write(g),
+        Format::Dimacs => dimacs::write(g),
+        Format::Mermaid => diagram::write_mermaid(g, None),
+        Format::PlantUml => diagram::write_plantuml(g, None),
+        Format::Svg => svg::write(g, &force_directed_layout(g, 100), &SvgStyle::default())?,
+    };
+    compression.compress(text.into_bytes())
+}
+
+impl GraphList {
+    /// Loads a graph in any readable format, reporting the format found.
+    pub fn load(path: impl AsRef<Path>) -> Result<(GraphList, Format), String> {
+        let path = path.as_ref();
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+        decode(&bytes, Format::from_extension(path))
+    }
+
+    /// Saves the graph as `format`, compressed when `path` ends in `.gz` or
+    /// `.zst`.
+    pub fn save(&self, path: impl AsRef<Path>, format: Format) -> Result<(), String> {
+        let path = path.as_ref();
+        let bytes = encode(self, format, Compression::from_extension(path))?;
+        std::fs::write(path, bytes).map_err(|e| format!("Cannot write {}: {e}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_graph(undirected: bool) -> GraphList {
+        let mut graph = GraphList::with_nodes(undirected, 0);
+        graph.insert_node(Some("A".to_string()));
+        graph.insert_node(None);
+        graph.insert_node(Some("C".to_string()));
+        graph.insert_node(None);
+        graph.insert_edge(0, 1, 2.0).unwrap();
+        graph.insert_edge(1, 2, 3.0).unwrap();
+        graph.insert_edge(3, 0, 1.0).unwrap();
+        graph
+    }
+
+    fn assert_same_edges(a: &GraphList, b: &GraphList) {
+        assert_eq!(a.num_nodes(), b.num_nodes());
+        for i in 0..a.num_nodes() {
+            for j in 0..a.num_nodes() {
+                assert_eq!(a.get_edge(i, j).unwrap(), b.get_edge(i, j).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_extension() {
+        let format = |path: &str| Format::from_extension(Path::new(path));
+        assert_eq!(format("a/b.mtx"), Some(Format::MatrixMarket));
+        assert_eq!(format("b.GML"), Some(Format::Gml));
+        assert_eq!(format("b.net.gz"), Some(Format::Pajek));
+        assert_eq!(format("b.graph.zst"), Some(Format::Metis));
+        assert_eq!(format("b.txt"), None);
+        assert_eq!(format("b.gz"), None);
+        assert_eq!(format("b"), None);
+        assert_eq!(
+            Compression::from_extension(Path::new("b.json.gz")),
+            Compression::Gzip
+        );
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Format::from_name("Pajek"), Some(Format::Pajek));
+        assert_eq!(Format::from_name("edge-list"), Some(Format::EdgeList));
+        assert_eq!(Format::from_name("net"), Some(Format::Pajek));
+        assert_eq!(Format::from_name("dot"), Some(Format::Dot));
+        assert_eq!(Format::from_name("gv"), Some(Format::Dot));
+        assert_eq!(Format::from_name("dia"), None);
+    }
+
+    #[test]
+    fn test_sniff() {
+        let graph = create_test_graph(false);
+        for format in [
+            Format::Binary,
+            Format::MatrixMarket,
+            Format::NodeLinkJson,
+            Format::AdjacencyJson,
+            Format::Gexf,
+            Format::GraphMl,
+            Format::Gml,
+            Format::Dot,
+            Format::Pajek,
+            Format::Leda,
+            Format::Csv,
+            Format::Dimacs,
+        ] {
+            let bytes = encode(&graph, format, Compression::None).unwrap();
+            assert_eq!(Format::sniff(&bytes), Some(format), "{format}");
+        }
+        let tsp = "NAME : x\nTYPE : TSP\nDIMENSION : 1\nEDGE_WEIGHT_TYPE : EUC_2D\n";
+        assert_eq!(Format::sniff(tsp.as_bytes()), Some(Format::Tsplib));
+        let dot = "// comment\nstrict graph \"G\" {\n a -- b\n}\n";
+        assert_eq!(Format::sniff(dot.as_bytes()), Some(Format::Dot));
+        let gr = "c USA road network\np sp 2 1\na 1 2 7\n";
+        assert_eq!(Format::sniff(gr.as_bytes()), Some(Format::Dimacs));
+        assert_eq!(Format::sniff(b"Source,Target\nx,y\n"), Some(Format::Csv));
+        assert_eq!(Format::sniff(b"3 2\n0 1\n1 2\n"), None);
+        assert_eq!(Format::sniff(&[0xff, 0xfe, 0]), None);
+    }
+
+    #[test]
+    fn test_decode_plain_numbers() {
+        let (g, format) = decode(b"3 2\n1 2 5\n2 3 1\n", None).unwrap();
+        assert_eq!(format, Format::EdgeList);
+        assert_eq!(g.get_edge(0, 1).unwrap().unwrap().weight.0, 5.0);
+
+        let (g, format) = decode(b"2\n0 1\n1 0\n", None).unwrap();
+        assert_eq!(format, Format::Matrix);
+        assert!(g.undirected);
+
+        // Three vertex lines for three vertices, but not three edges.
+        let (g, format) = decode(b"3 2\n2\n1 3\n2\n", None).unwrap();
+        assert_eq!(format, Format::Metis);
+        assert!(g.is_edge(2, 1));
+
+        let (g, format) = decode(b"# Directed graph\n10 20\n20 30\n", None).unwrap();
+        assert_eq!(format, Format::Snap);
+        assert_eq!(g.num_nodes(), 3);
+
+        assert!(decode(b"not a graph", None).is_err());
+        // The extension decides when the content does not.
+        let (_, format) = decode(b"3 2\n2\n1 3\n2\n", Some(Format::Metis)).unwrap();
+        assert_eq!(format, Format::Metis);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("algo3-file-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let directed = create_test_graph(false);
+        let undirected = create_test_graph(true);
+        let cases = [
+            ("g.ag3", Format::Binary, &directed),
+            ("g.mtx.gz", Format::MatrixMarket, &directed),
+            ("g.json.zst", Format::NodeLinkJson, &undirected),
+            ("g.json", Format::AdjacencyJson, &directed),
+            ("g.gexf", Format::Gexf, &undirected),
+            ("g.gml.gz", Format::Gml, &directed),
+            ("g.net", Format::Pajek, &undirected),
+            ("g.gw", Format::Leda, &directed),
+            ("g.graph", Format::Metis, &undirected),
+            ("g.snap", Format::Snap, &directed),
+            ("g.edges.zst", Format::EdgeList, &directed),
+            ("g.graphml", Format::GraphMl, &undirected),
+            ("g.gv", Format::Dot, &directed),
+            ("g.csv", Format::Csv, &directed),
+            ("g.gr", Format::Dimacs, &directed),
+            // No marker and an unknown extension: detected from the numbers.
+            ("g.txt", Format::Matrix, &directed),
+        ];
+        for (name, format, graph) in cases {
+            let path = dir.join(name);
+            graph.save(&path, format).unwrap();
+            let compressed = Compression::detect(&std::fs::read(&path).unwrap());
+            assert_eq!(compressed, Compression::from_extension(&path), "{name}");
+            let (read, detected) = GraphList::load(&path).unwrap();
+            assert_eq!(detected, format, "{name}");
+            assert_same_edges(&read, graph);
+        }
+
+        let svg = dir.join("g.svg");
+        directed.save(&svg, Format::Svg).unwrap();
+        assert!(GraphList::load(&svg).is_err());
+        assert!(directed.save(dir.join("g.tsp"), Format::Tsplib).is_err());
+        assert!(GraphList::load(dir.join("missing.gml")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
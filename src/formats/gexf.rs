@@ -0,0 +1,456 @@
+use super::xml::{self, escape, Element};
+use crate::graph::GraphList;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// GEXF (https://gexf.net), the native format of Gephi. Besides nodes, labels
+// and weighted edges, dynamic graphs give nodes and edges time spells
+// (`start`/`end` attributes or `<spells>` children) and nodes time-dependent
+// attribute values (`<attvalue for=".." value=".." start=".." end=".."/>`).
+// Times are kept as the strings found in the file, their meaning depends on
+// the graph's `timeformat`.
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Spell {
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+/// Value of a node attribute, valid during `spell`. `attribute` is the
+/// declared title of the attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttValue {
+    pub attribute: String,
+    pub value: String,
+    pub spell: Spell,
+}
+
+#[derive(Clone, Debug)]
+pub struct GexfGraph {
+    pub graph: GraphList,
+    pub time_format: Option<String>,
+    pub node_spells: Vec<Vec<Spell>>,
+    pub node_attributes: Vec<Vec<AttValue>>,
+    /// Spells of the edge from the first node to the second one, as written
+    /// in the file.
+    pub edge_spells: HashMap<(usize, usize), Vec<Spell>>,
+}
+
+impl GexfGraph {
+    pub fn is_dynamic(&self) -> bool {
+        self.node_spells.iter().any(|spells| !spells.is_empty())
+            || self.edge_spells.values().any(|spells| !spells.is_empty())
+            || self
+                .node_attributes
+                .iter()
+                .flatten()
+                .any(|value| value.spell != Spell::default())
+    }
+}
+
+impl From<GraphList> for GexfGraph {
+    fn from(graph: GraphList) -> Self {
+        let n = graph.num_nodes();
+        GexfGraph {
+            graph,
+            time_format: None,
+            node_spells: vec![vec![]; n],
+            node_attributes: vec![vec![]; n],
+            edge_spells: HashMap::new(),
+        }
+    }
+}
+
+fn spell_of(element: &Element) -> Option<Spell> {
+    let start = element.attribute("start").map(str::to_string);
+    let end = element.attribute("end").map(str::to_string);
+    (start.is_some() || end.is_some()).then_some(Spell { start, end })
+}
+
+// Spells given as attributes of the element itself or as `<spells>` children.
+fn spells_of(element: &Element) -> Vec<Spell> {
+    let mut spells: Vec<Spell> = spell_of(element).into_iter().collect();
+    if let Some(list) = element.child("spells") {
+        spells.extend(list.children_named("spell").filter_map(spell_of));
+    }
+    spells
+}
+
+pub fn read(input: &str) -> Result<GexfGraph, String> {
+    let root = xml::parse(input)?;
+    if root.name != "gexf" {
+        return Err(format!("Expected a <gexf> document, found <{}>", root.name));
+    }
+    let graph = root.child("graph").ok_or("Missing <graph> element")?;
+    let default_type = graph.attribute("defaultedgetype").unwrap_or("undirected");
+
+    let mut titles = HashMap::new();
+    for declarations in graph.children_named("attributes") {
+        if declarations.attribute("class") != Some("node") {
+            continue;
+        }
+        for attribute in declarations.children_named("attribute") {
+            let id = attribute.attribute("id").ok_or("Attribute without an id")?;
+            let title = attribute.attribute("title").unwrap_or(id);
+            titles.insert(id.to_string(), title.to_string());
+        }
+    }
+
+    let mut gexf = GexfGraph::from(GraphList::with_nodes(default_type != "directed", 0));
+    gexf.time_format = graph.attribute("timeformat").map(str::to_string);
+    let mut indices = HashMap::new();
+    let nodes = graph
+        .child("nodes")
+        .map(|nodes| nodes.children_named("node"));
+    for node in nodes.into_iter().flatten() {
+        let id = node.attribute("id").ok_or("Node without an id")?;
+        let index = gexf.graph.num_nodes();
+        if indices.insert(id.to_string(), index).is_some() {
+            return Err(format!("Duplicate node id: {id}"));
+        }
+        gexf.graph
+            .insert_node(node.attribute("label").map(str::to_string));
+        gexf.node_spells.push(spells_of(node));
+        let mut values = vec![];
+        if let Some(attvalues) = node.child("attvalues") {
+            for attvalue in attvalues.children_named("attvalue") {
+                let key = attvalue
+                    .attribute("for")
+                    .ok_or(format!("Attribute value of node {id} without `for`"))?;
+                values.push(AttValue {
+                    attribute: titles.get(key).cloned().unwrap_or(key.to_string()),
+                    value: attvalue.attribute("value").unwrap_or("").to_string(),
+                    spell: spell_of(attvalue).unwrap_or_default(),
+                });
+            }
+        }
+        gexf.node_attributes.push(values);
+    }
+
+    let mut edges = vec![];
+    let edge_elements = graph
+        .child("edges")
+        .map(|edges| edges.children_named("edge"));
+    for edge in edge_elements.into_iter().flatten() {
+        let endpoint = |name: &str| {
+            let id = edge
+                .attribute(name)
+                .ok_or(format!("Edge without a {name}"))?;
+            indices
+                .get(id)
+                .copied()
+                .ok_or(format!("Edge refers to unknown node: {id}"))
+        };
+        let (from, to) = (endpoint("source")?, endpoint("target")?);
+        let weight = match edge.attribute("weight") {
+            Some(w) => w.parse().map_err(|_| format!("Invalid edge weight: {w}"))?,
+            None => 1.0,
+        };
+        let directed = edge.attribute("type").unwrap_or(default_type) == "directed";
+        edges.push((from, to, weight, directed));
+        let spells = spells_of(edge);
+        if !spells.is_empty() {
+            gexf.edge_spells.insert((from, to), spells);
+        }
+    }
+    // A graph mixing both kinds of edges is read as directed, with undirected
+    // edges turned into a pair of arcs.
+    gexf.graph.undirected = if edges.is_empty() {
+        default_type != "directed"
+    } else {
+        edges.iter().all(|&(.., directed)| !directed)
+    };
+    for (from, to, weight, directed) in edges {
+        gexf.graph.insert_edge(from, to, weight)?;
+        if !directed && !gexf.graph.undirected {
+            gexf.graph.insert_edge(to, from, weight)?;
+        }
+    }
+    Ok(gexf)
+}
+
+fn spell_attributes(spell: &Spell) -> String {
+    let mut out = String::new();
+    if let Some(start) = &spell.start {
+        write!(out, " start=\"{}\"", escape(start)).unwrap();
+    }
+    if let Some(end) = &spell.end {
+        write!(out, " end=\"{}\"", escape(end)).unwrap();
+    }
+    out
+}
+
+fn write_spells(out: &mut String, spells: &[Spell], indent: &str) {
+    if spells.is_empty() {
+        return;
+    }
+    writeln!(out, "{indent}<spells>").unwrap();
+    for spell in spells {
+        writeln!(out, "{indent}  <spell{}/>", spell_attributes(spell)).unwrap();
+    }
+    writeln!(out, "{indent}</spells>").unwrap();
+}
+
+pub fn write(gexf: &GexfGraph) -> String {
+    let g = &gexf.graph;
+    let edge_type = if g.undirected {
+        "undirected"
+    } else {
+        "directed"
+    };
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+    write!(out, "  <graph defaultedgetype=\"{edge_type}\"").unwrap();
+    if gexf.is_dynamic() {
+        out.push_str(" mode=\"dynamic\"");
+        if let Some(time_format) = &gexf.time_format {
+            write!(out, " timeformat=\"{}\"", escape(time_format)).unwrap();
+        }
+    } else {
+        out.push_str(" mode=\"static\"");
+    }
+    out.push_str(">\n");
+
+    let mut attribute_ids: Vec<&str> = vec![];
+    for value in gexf.node_attributes.iter().flatten() {
+        if !attribute_ids.contains(&value.attribute.as_str()) {
+            attribute_ids.push(&value.attribute);
+        }
+    }
+    if !attribute_ids.is_empty() {
+        let mode = if gexf.is_dynamic() {
+            "dynamic"
+        } else {
+            "static"
+        };
+        writeln!(out, "    <attributes class=\"node\" mode=\"{mode}\">").unwrap();
+        for (id, title) in attribute_ids.iter().enumerate() {
+            writeln!(
+                out,
+                "      <attribute id=\"{id}\" title=\"{}\" type=\"string\"/>",
+                escape(title)
+            )
+            .unwrap();
+        }
+        out.push_str("    </attributes>\n");
+    }
+
+    out.push_str("    <nodes>\n");
+    for node in &g.nodes {
+        let i = node.index();
+        write!(out, "      <node id=\"{i}\"").unwrap();
+        if let Some(label) = node.label() {
+            write!(out, " label=\"{}\"", escape(label)).unwrap();
+        }
+        let spells = gexf.node_spells.get(i).map_or(&[][..], Vec::as_slice);
+        let values = gexf.node_attributes.get(i).map_or(&[][..], Vec::as_slice);
+        if spells.is_empty() && values.is_empty() {
+            out.push_str("/>\n");
+            continue;
+        }
+        out.push_str(">\n");
+        if !values.is_empty() {
+            out.push_str("        <attvalues>\n");
+            for value in values {
+                let id = attribute_ids
+                    .iter()
+                    .position(|&id| id == value.attribute)
+                    .unwrap();
+                writeln!(
+                    out,
+                    "          <attvalue for=\"{id}\" value=\"{}\"{}/>",
+                    escape(&value.value),
+                    spell_attributes(&value.spell)
+                )
+                .unwrap();
+            }
+            out.push_str("        </attvalues>\n");
+        }
+        write_spells(&mut out, spells, "        ");
+        out.push_str("      </node>\n");
+    }
+    out.push_str("    </nodes>\n");
+
+    out.push_str("    <edges>\n");
+    let edges = g
+        .nodes
+        .iter()
+        .flat_map(|node| node.get_ordered_edge_list())
+        .filter(|e| !g.undirected || e.from <= e.to);
+    for (id, e) in edges.enumerate() {
+        write!(
+            out,
+            "      <edge id=\"{id}\" source=\"{}\" target=\"{}\" weight=\"{}\"",
+            e.from, e.to, e.weight
+        )
+        .unwrap();
+        let spells = gexf.edge_spells.get(&(e.from, e.to)).or_else(|| {
+            g.undirected
+                .then(|| gexf.edge_spells.get(&(e.to, e.from)))
+                .flatten()
+        });
+        match spells {
+            Some(spells) if !spells.is_empty() => {
+                out.push_str(">\n");
+                write_spells(&mut out, spells, "        ");
+                out.push_str("      </edge>\n");
+            }
+            _ => out.push_str("/>\n"),
+        }
+    }
+    out.push_str("    </edges>\n");
+    out.push_str("  </graph>\n</gexf>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    #[test]
+    fn test_read_static() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gexf xmlns="http://gexf.net/1.3" version="1.3">
+  <meta><creator>Gephi</creator></meta>
+  <graph mode="static" defaultedgetype="directed">
+    <nodes>
+      <node id="a" label="Hello"/>
+      <node id="b" label="Word"/>
+      <node id="c"/>
+    </nodes>
+    <edges>
+      <edge id="0" source="a" target="b" weight="2.5"/>
+      <edge id="1" source="b" target="c"/>
+    </edges>
+  </graph>
+</gexf>
+"#;
+        let gexf = read(input).unwrap();
+        let g = &gexf.graph;
+        assert!(!g.undirected);
+        assert_eq!(g.nodes[0].label(), Some("Hello"));
+        assert_eq!(g.nodes[2].label(), None);
+        assert_eq!(g.get_edge(0, 1).unwrap().unwrap().weight, OrderedFloat(2.5));
+        assert_eq!(g.get_edge(1, 2).unwrap().unwrap().weight, OrderedFloat(1.0));
+        assert!(!g.is_edge(1, 0));
+        assert!(!gexf.is_dynamic());
+    }
+
+    #[test]
+    fn test_read_dynamic() {
+        let input = r#"<gexf version="1.3">
+  <graph mode="dynamic" timeformat="double">
+    <attributes class="node" mode="dynamic">
+      <attribute id="0" title="score" type="float"/>
+    </attributes>
+    <nodes>
+      <node id="0" label="A" start="1.0">
+        <attvalues>
+          <attvalue for="0" value="3" start="1.0" end="2.0"/>
+          <attvalue for="0" value="4" start="2.0"/>
+        </attvalues>
+      </node>
+      <node id="1">
+        <spells>
+          <spell start="1.0" end="3.0"/>
+          <spell start="5.0"/>
+        </spells>
+      </node>
+    </nodes>
+    <edges>
+      <edge source="0" target="1" start="2.0" end="3.0"/>
+    </edges>
+  </graph>
+</gexf>"#;
+        let gexf = read(input).unwrap();
+        assert!(gexf.graph.undirected);
+        assert!(gexf.graph.is_edge(1, 0));
+        assert!(gexf.is_dynamic());
+        assert_eq!(
+            gexf.node_spells[0],
+            vec![Spell {
+                start: Some("1.0".to_string()),
+                end: None
+            }]
+        );
+        assert_eq!(gexf.node_spells[1].len(), 2);
+        assert_eq!(gexf.node_attributes[0][1].attribute, "score");
+        assert_eq!(gexf.node_attributes[0][1].value, "4");
+        assert_eq!(gexf.edge_spells[&(0, 1)][0].end.as_deref(), Some("3.0"));
+    }
+
+    #[test]
+    fn test_read_mixed_edge_types() {
+        let input = r#"<gexf><graph defaultedgetype="undirected"><nodes>
+            <node id="0"/><node id="1"/><node id="2"/>
+        </nodes><edges>
+            <edge source="0" target="1"/>
+            <edge source="1" target="2" type="directed"/>
+        </edges></graph></gexf>"#;
+        let g = read(input).unwrap().graph;
+        assert!(!g.undirected);
+        assert!(g.is_edge(0, 1) && g.is_edge(1, 0));
+        assert!(g.is_edge(1, 2) && !g.is_edge(2, 1));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("<graphml/>").is_err());
+        assert!(read("<gexf></gexf>").is_err());
+        assert!(
+            read(r#"<gexf><graph><nodes><node id="0"/><node id="0"/></nodes></graph></gexf>"#)
+                .is_err()
+        );
+        assert!(read(
+            r#"<gexf><graph><edges><edge source="0" target="1"/></edges></graph></gexf>"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut graph = GraphList::with_nodes(true, 0);
+        graph.insert_node(Some("<A & B>".to_string()));
+        graph.insert_node(None);
+        graph.insert_node(Some("C".to_string()));
+        graph.insert_edge(0, 1, 1.5).unwrap();
+        graph.insert_edge(2, 1, -1.0).unwrap();
+        let mut gexf = GexfGraph::from(graph);
+        gexf.time_format = Some("date".to_string());
+        gexf.node_spells[1].push(Spell {
+            start: Some("2020-01-01".to_string()),
+            end: Some("2021-01-01".to_string()),
+        });
+        gexf.node_attributes[2].push(AttValue {
+            attribute: "kind".to_string(),
+            value: "hub".to_string(),
+            spell: Spell::default(),
+        });
+        gexf.edge_spells.insert(
+            (2, 1),
+            vec![Spell {
+                start: None,
+                end: Some("2020-06-01".to_string()),
+            }],
+        );
+
+        let written = write(&gexf);
+        assert!(written.contains("mode=\"dynamic\" timeformat=\"date\""));
+        let read = read(&written).unwrap();
+        assert!(read.graph.undirected);
+        assert_eq!(read.time_format, gexf.time_format);
+        assert_eq!(read.node_spells, gexf.node_spells);
+        assert_eq!(read.node_attributes, gexf.node_attributes);
+        assert_eq!(read.edge_spells[&(1, 2)], gexf.edge_spells[&(2, 1)]);
+        for i in 0..3 {
+            assert_eq!(read.graph.nodes[i].label(), gexf.graph.nodes[i].label());
+            for j in 0..3 {
+                assert_eq!(
+                    read.graph.get_edge(i, j).unwrap(),
+                    gexf.graph.get_edge(i, j).unwrap()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use super::xml;
+use crate::graph::GraphList;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// GML (Graph Modelling Language): a tree of `key value` pairs where values
+// are integers, reals, double-quoted strings or `[ ... ]` lists.
+//
+// graph [
+//   directed 1
+//   node [ id 0 label "A" ]
+//   edge [ source 0 target 1 weight 2.5 ]
+// ]
+//
+// Quotes and ampersands inside strings are written as `&quot;` and `&amp;`.
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Int(i64),
+    Real(f64),
+    Str(String),
+    List(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(i) => Some(*i as f64),
+            Value::Real(r) => Some(*r),
+            _ => None,
+        }
+    }
+}
+
+fn get<'a>(list: &'a [(String, Value)], key: &str) -> Option<&'a Value> {
+    list.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Key(String),
+    Value(Value),
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, String> {
+    let mut tokens = vec![];
+    let mut chars = input.char_indices().peekable();
+    let mut line = 1;
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            '\n' => {
+                line += 1;
+                chars.next();
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '#' => while chars.next_if(|&(_, c)| c != '\n').is_some() {},
+            '[' => {
+                tokens.push((line, Token::Open));
+                chars.next();
+            }
+            ']' => {
+                tokens.push((line, Token::Close));
+                chars.next();
+            }
+            '"' => {
+                chars.next();
+                let mut end = None;
+                for (i, c) in chars.by_ref() {
+                    if c == '"' {
+                        end = Some(i);
+                        break;
+                    }
+                    if c == '\n' {
+                        line += 1;
+                    }
+                }
+                let end = end.ok_or(format!("Line {line}: unterminated string"))?;
+                let raw = &input[start + 1..end];
+                let text = xml::unescape(raw).unwrap_or(raw.to_string());
+                tokens.push((line, Token::Value(Value::Str(text))));
+            }
+            _ => {
+                let mut end = input.len();
+                while let Some(&(i, c)) = chars.peek() {
+                    if c.is_whitespace() || c == '[' || c == ']' || c == '"' {
+                        end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                let word = &input[start..end];
+                let token = if word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+                    Token::Key(word.to_string())
+                } else if let Ok(i) = word.parse() {
+                    Token::Value(Value::Int(i))
+                } else if let Ok(r) = word.parse() {
+                    Token::Value(Value::Real(r))
+                } else {
+                    return Err(format!("Line {line}: unexpected `{word}`"));
+                };
+                tokens.push((line, token));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse(input: &str) -> Result<Vec<(String, Value)>, String> {
+    // Lists being built, innermost last, with the key each one belongs to.
+    let mut stack: Vec<(String, Vec<(String, Value)>)> = vec![(String::new(), vec![])];
+    let mut key: Option<String> = None;
+    for (line, token) in tokenize(input)? {
+        match (token, key.take()) {
+            (Token::Key(k), None) => key = Some(k),
+            (Token::Value(value), Some(k)) => stack.last_mut().unwrap().1.push((k, value)),
+            (Token::Open, Some(k)) => stack.push((k, vec![])),
+            (Token::Close, None) if stack.len() > 1 => {
+                let (k, list) = stack.pop().unwrap();
+                stack.last_mut().unwrap().1.push((k, Value::List(list)));
+            }
+            (Token::Close, None) => return Err(format!("Line {line}: unmatched `]`")),
+            (_, Some(k)) => return Err(format!("Line {line}: missing value for `{k}`")),
+            (_, None) => return Err(format!("Line {line}: expected a key")),
+        }
+    }
+    if let Some(k) = key {
+        return Err(format!("Missing value for `{k}`"));
+    }
+    if stack.len() > 1 {
+        return Err(format!("Unclosed list `{}`", stack.last().unwrap().0));
+    }
+    Ok(stack.pop().unwrap().1)
+}
+
+pub fn read(input: &str) -> Result<GraphList, String> {
+    let document = parse(input)?;
+    let Some(Value::List(graph)) = get(&document, "graph") else {
+        return Err("Missing `graph [ ... ]`".to_string());
+    };
+    let directed = matches!(get(graph, "directed"), Some(Value::Int(1)));
+    let mut g = GraphList::with_nodes(!directed, 0);
+    let mut indices = HashMap::new();
+    for (key, value) in graph {
+        if let ("node", Value::List(node)) = (key.as_str(), value) {
+            let Some(Value::Int(id)) = get(node, "id") else {
+                return Err("Node without an integer id".to_string());
+            };
+            if indices.insert(*id, g.num_nodes()).is_some() {
+                return Err(format!("Duplicate node id: {id}"));
+            }
+            let label = match get(node, "label") {
+                Some(Value::Str(label)) => Some(label.clone()),
+                Some(Value::Int(i)) => Some(i.to_string()),
+                Some(Value::Real(r)) => Some(r.to_string()),
+                _ => None,
+            };
+            g.insert_node(label);
+        }
+    }
+    for (key, value) in graph {
+        if let ("edge", Value::List(edge)) = (key.as_str(), value) {
+            let endpoint = |name: &str| match get(edge, name) {
+                Some(Value::Int(id)) => indices
+                    .get(id)
+                    .copied()
+                    .ok_or(format!("Edge refers to unknown node: {id}")),
+                _ => Err(format!("Edge without an integer {name}")),
+            };
+            let (from, to) = (endpoint("source")?, endpoint("target")?);
+            let weight = match get(edge, "weight").or_else(|| get(edge, "value")) {
+                Some(w) => w.as_f64().ok_or("Edge weight must be a number")?,
+                None => 1.0,
+            };
+            g.insert_edge(from, to, weight)?;
+        }
+    }
+    Ok(g)
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('&', "&amp;").replace('"', "&quot;"))
+}
+
+/// Writes `g` with node labels and edge weights. Fails on infinite or NaN
+/// weights, which GML numbers cannot represent.
+pub fn write(g: &GraphList) -> Result<String, String> {
+    let mut out = String::from("graph [\n");
+    writeln!(out, "  directed {}", if g.undirected { 0 } else { 1 }).unwrap();
+    for node in &g.nodes {
+        out.push_str("  node [\n");
+        writeln!(out, "    id {}", node.index()).unwrap();
+        if let Some(label) = node.label() {
+            writeln!(out, "    label {}", quote(label)).unwrap();
+        }
+        out.push_str("  ]\n");
+    }
+    for node in &g.nodes {
+        for e in node.get_ordered_edge_list() {
+            if g.undirected && e.from > e.to {
+                continue;
+            }
+            if !e.weight.0.is_finite() {
+                return Err(format!(
+                    "GML cannot store the weight {} of edge {} - {}",
+                    e.weight, e.from, e.to
+                ));
+            }
+            out.push_str("  edge [\n");
+            writeln!(out, "    source {}", e.from).unwrap();
+            writeln!(out, "    target {}", e.to).unwrap();
+            // Keep integral weights readable as reals by other tools.
+            writeln!(out, "    weight {:?}", e.weight.0).unwrap();
+            out.push_str("  ]\n");
+        }
+    }
+    out.push_str("]\n");
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    #[test]
+    fn test_parse() {
+        let parsed = parse("a 1 b -2.5e1 # comment\nc \"x &quot;y&quot;\" d [ e [ ] ]").unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                ("a".to_string(), Value::Int(1)),
+                ("b".to_string(), Value::Real(-25.0)),
+                ("c".to_string(), Value::Str("x \"y\"".to_string())),
+                (
+                    "d".to_string(),
+                    Value::List(vec![("e".to_string(), Value::List(vec![]))])
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("a").is_err());
+        assert!(parse("a [").is_err());
+        assert!(parse("]").is_err());
+        assert!(parse("1 2").is_err());
+        assert!(parse("a \"open").is_err());
+        assert!(parse("a 1x").is_err());
+    }
+
+    #[test]
+    fn test_read() {
+        let input = r#"Creator "yFiles"
+graph
+[
+  directed 1
+  node [ id 10 label "A" graphics [ x 1.0 ] ]
+  node [ id 20 ]
+  node [ id 30 label "C" ]
+  edge [ source 10 target 20 value 3 ]
+  edge [ source 20 target 30 ]
+]"#;
+        let g = read(input).unwrap();
+        assert!(!g.undirected);
+        assert_eq!(g.num_nodes(), 3);
+        assert_eq!(g.nodes[0].label(), Some("A"));
+        assert_eq!(g.nodes[1].label(), None);
+        assert_eq!(g.get_edge(0, 1).unwrap().unwrap().weight, OrderedFloat(3.0));
+        assert_eq!(g.get_edge(1, 2).unwrap().unwrap().weight, OrderedFloat(1.0));
+        assert!(!g.is_edge(1, 0));
+    }
+
+    #[test]
+    fn test_read_defaults_to_undirected() {
+        let g = read("graph [ node [ id 0 ] node [ id 1 ] edge [ source 0 target 1 ] ]").unwrap();
+        assert!(g.undirected);
+        assert!(g.is_edge(1, 0));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("").is_err());
+        assert!(read("graph [ node [ label \"x\" ] ]").is_err());
+        assert!(read("graph [ node [ id 0 ] node [ id 0 ] ]").is_err());
+        assert!(read("graph [ node [ id 0 ] edge [ source 0 target 1 ] ]").is_err());
+        assert!(read("graph [ node [ id 0 ] edge [ source 0 target 0 weight \"x\" ] ]").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for undirected in [false, true] {
+            let mut graph = GraphList::with_nodes(undirected, 0);
+            graph.insert_node(Some("say \"hi\" & bye".to_string()));
+            graph.insert_node(None);
+            graph.insert_node(Some("C".to_string()));
+            graph.insert_edge(0, 1, 2.0).unwrap();
+            graph.insert_edge(2, 0, -0.5).unwrap();
+            graph.insert_edge(1, 1, 1.0).unwrap();
+            let read = read(&write(&graph).unwrap()).unwrap();
+            assert_eq!(read.undirected, undirected);
+            for i in 0..3 {
+                assert_eq!(read.nodes[i].label(), graph.nodes[i].label());
+                for j in 0..3 {
+                    assert_eq!(read.get_edge(i, j).unwrap(), graph.get_edge(i, j).unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_non_finite_weight() {
+        let mut graph = GraphList::with_nodes(false, 2);
+        graph.insert_edge(0, 1, f64::INFINITY).unwrap();
+        assert_eq!(
+            write(&graph).unwrap_err(),
+            "GML cannot store the weight inf of edge 0 - 1"
+        );
+    }
+}
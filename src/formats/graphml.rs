@@ -0,0 +1,225 @@
+use super::xml::{self, escape, Element};
+use crate::graph::GraphList;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// GraphML (http://graphml.graphdrawing.org). Node labels and edge weights
+// are `<data>` values of the keys named `label` and `weight`; other keys,
+// ports, nested graphs and hyperedges are ignored.
+
+// The key named `name` for elements of kind `domain`, with its default.
+fn key<'a>(root: &'a Element, domain: &str, name: &str) -> Option<(&'a str, Option<String>)> {
+    root.children_named("key")
+        .find(|key| {
+            key.attribute("attr.name") == Some(name)
+                && matches!(key.attribute("for"), Some(d) if d == domain || d == "all")
+        })
+        .and_then(|key| {
+            let default = key.child("default").map(|d| d.text.trim().to_string());
+            Some((key.attribute("id")?, default))
+        })
+}
+
+// The value of key `id` in `element`, falling back on the key's default.
+fn data(element: &Element, key: Option<&(&str, Option<String>)>) -> Option<String> {
+    let (id, default) = key?;
+    element
+        .children_named("data")
+        .find(|data| data.attribute("key") == Some(id))
+        .map(|data| data.text.trim().to_string())
+        .or(default.clone())
+}
+
+/// Reads the first graph of a GraphML document. A graph mixing directed and
+/// undirected edges is read as directed, with every undirected edge
+/// inserted in both directions.
+pub fn read(input: &str) -> Result<GraphList, String> {
+    let root = xml::parse(input)?;
+    if root.name != "graphml" {
+        return Err(format!(
+            "Expected a <graphml> document, found <{}>",
+            root.name
+        ));
+    }
+    let graph = root.child("graph").ok_or("Missing <graph> element")?;
+    let default_directed = match graph.attribute("edgedefault") {
+        Some("directed") => true,
+        Some("undirected") => false,
+        Some(other) => return Err(format!("Invalid edgedefault: {other}")),
+        None => return Err("Missing edgedefault".to_string()),
+    };
+    let label_key = key(&root, "node", "label");
+    let weight_key = key(&root, "edge", "weight");
+
+    let mut g = GraphList::with_nodes(!default_directed, 0);
+    let mut indices = HashMap::new();
+    for node in graph.children_named("node") {
+        let id = node.attribute("id").ok_or("Node without an id")?;
+        if indices.insert(id, g.num_nodes()).is_some() {
+            return Err(format!("Duplicate node id: {id}"));
+        }
+        g.insert_node(data(node, label_key.as_ref()));
+    }
+
+    let mut edges = vec![];
+    for edge in graph.children_named("edge") {
+        let endpoint = |name: &str| {
+            let id = edge
+                .attribute(name)
+                .ok_or(format!("Edge without a {name}"))?;
+            indices
+                .get(id)
+                .copied()
+                .ok_or(format!("Edge refers to unknown node: {id}"))
+        };
+        let (from, to) = (endpoint("source")?, endpoint("target")?);
+        let weight = match data(edge, weight_key.as_ref()) {
+            Some(w) => w.parse().map_err(|_| format!("Invalid edge weight: {w}"))?,
+            None => 1.0,
+        };
+        let directed = match edge.attribute("directed") {
+            Some("true") => true,
+            Some("false") => false,
+            Some(other) => return Err(format!("Invalid directed attribute: {other}")),
+            None => default_directed,
+        };
+        edges.push((from, to, weight, directed));
+    }
+    if edges.iter().any(|&(.., directed)| directed) {
+        g.undirected = false;
+    }
+    for (from, to, weight, directed) in edges {
+        g.insert_edge(from, to, weight)?;
+        if !directed && !g.undirected {
+            g.insert_edge(to, from, weight)?;
+        }
+    }
+    Ok(g)
+}
+
+/// Writes `g` with a `label` key for nodes and a `weight` key for edges.
+pub fn write(g: &GraphList) -> String {
+    let edge_default = if g.undirected {
+        "undirected"
+    } else {
+        "directed"
+    };
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    out.push_str("  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n");
+    out.push_str("  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n");
+    writeln!(out, "  <graph edgedefault=\"{edge_default}\">").unwrap();
+    for node in &g.nodes {
+        match node.label() {
+            Some(label) => writeln!(
+                out,
+                "    <node id=\"n{}\"><data key=\"label\">{}</data></node>",
+                node.index(),
+                escape(label)
+            )
+            .unwrap(),
+            None => writeln!(out, "    <node id=\"n{}\"/>", node.index()).unwrap(),
+        }
+    }
+    for node in &g.nodes {
+        for e in node.get_ordered_edge_list() {
+            if g.undirected && e.from > e.to {
+                continue;
+            }
+            writeln!(
+                out,
+                "    <edge source=\"n{}\" target=\"n{}\"><data key=\"weight\">{}</data></edge>",
+                e.from, e.to, e.weight
+            )
+            .unwrap();
+        }
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    fn weight(g: &GraphList, from: usize, to: usize) -> Option<OrderedFloat<f64>> {
+        g.get_edge(from, to).unwrap().map(|e| e.weight)
+    }
+
+    #[test]
+    fn test_read() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="label" attr.type="string"/>
+  <key id="d1" for="edge" attr.name="weight" attr.type="double">
+    <default>2.5</default>
+  </key>
+  <graph id="G" edgedefault="undirected">
+    <node id="a"><data key="d0">First &amp; last</data></node>
+    <node id="b"/>
+    <node id="c"/>
+    <edge source="a" target="b"/>
+    <edge source="b" target="c"><data key="d1">-1</data></edge>
+  </graph>
+</graphml>
+"#;
+        let g = read(input).unwrap();
+        assert!(g.undirected);
+        assert_eq!(g.nodes[0].label(), Some("First & last"));
+        assert_eq!(g.nodes[1].label(), None);
+        assert_eq!(weight(&g, 1, 0), Some(OrderedFloat(2.5)));
+        assert_eq!(weight(&g, 2, 1), Some(OrderedFloat(-1.0)));
+    }
+
+    #[test]
+    fn test_read_mixed_edges() {
+        let input = r#"<graphml><graph edgedefault="undirected">
+            <node id="0"/><node id="1"/><node id="2"/>
+            <edge source="0" target="1"/>
+            <edge source="1" target="2" directed="true"/>
+        </graph></graphml>"#;
+        let g = read(input).unwrap();
+        assert!(!g.undirected);
+        assert!(g.is_edge(0, 1) && g.is_edge(1, 0));
+        assert!(g.is_edge(1, 2) && !g.is_edge(2, 1));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("<gexf/>").is_err());
+        assert!(read("<graphml></graphml>").is_err());
+        assert!(read(r#"<graphml><graph/></graphml>"#).is_err());
+        let graph = |body: &str| {
+            format!(r#"<graphml><graph edgedefault="directed">{body}</graph></graphml>"#)
+        };
+        assert!(read(&graph(r#"<node id="a"/><node id="a"/>"#)).is_err());
+        assert!(read(&graph(r#"<node id="a"/><edge source="a" target="b"/>"#)).is_err());
+        assert!(read(&graph(r#"<node/>"#)).is_err());
+        let weighted = r#"<graphml><key id="w" for="edge" attr.name="weight"/>
+            <graph edgedefault="directed"><node id="a"/>
+            <edge source="a" target="a"><data key="w">heavy</data></edge></graph></graphml>"#;
+        assert_eq!(read(weighted).unwrap_err(), "Invalid edge weight: heavy");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for undirected in [false, true] {
+            let mut graph = GraphList::with_nodes(undirected, 0);
+            graph.insert_node(Some("<A & B>".to_string()));
+            graph.insert_node(None);
+            graph.insert_node(Some("C".to_string()));
+            graph.insert_edge(0, 1, 2.0).unwrap();
+            graph.insert_edge(2, 0, -0.5).unwrap();
+            graph.insert_edge(1, 1, 1.0).unwrap();
+            let read = read(&write(&graph)).unwrap();
+            assert_eq!(read.undirected, undirected);
+            for i in 0..3 {
+                assert_eq!(read.nodes[i].label(), graph.nodes[i].label());
+                for j in 0..3 {
+                    assert_eq!(read.get_edge(i, j).unwrap(), graph.get_edge(i, j).unwrap());
+                }
+            }
+        }
+    }
+}
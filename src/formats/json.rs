@@ -0,0 +1,382 @@
+use crate::graph::GraphList;
+use ordered_float::OrderedFloat;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+// Node-link JSON, as used by D3 and networkx:
+// {"directed": true, "nodes": [{"id": 0, "label": "A"}],
+//  "links": [{"source": 0, "target": 1, "weight": 2.0}]}
+// and adjacency JSON, where `links` is replaced by one list of
+// {"id": .., "weight": ..} neighbors per node:
+// {"directed": true, "nodes": [...], "adjacency": [[{"id": 1, "weight": 2.0}], []]}
+
+/// Algorithm results that can be attached to each node of an exported graph.
+/// Infinite distances and `-1` parents/components are written as `null`.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct NodeAnnotations {
+    pub distance: Option<Vec<OrderedFloat<f64>>>,
+    pub component: Option<Vec<isize>>,
+    pub parent: Option<Vec<isize>>,
+}
+
+fn optional_index(value: isize) -> Value {
+    if value < 0 {
+        Value::Null
+    } else {
+        json!(value)
+    }
+}
+
+fn node_objects(g: &GraphList, annotations: &NodeAnnotations) -> Vec<Value> {
+    let mut nodes = vec![];
+    for node in &g.nodes {
+        let i = node.index();
+        let mut object = Map::new();
+        object.insert("id".to_string(), json!(i));
+        if let Some(label) = node.label() {
+            object.insert("label".to_string(), json!(label));
+        }
+        if let Some(distance) = annotations.distance.as_ref().and_then(|d| d.get(i)) {
+            // JSON has no infinity, serde_json writes non-finite floats as null.
+            object.insert("distance".to_string(), json!(distance.0));
+        }
+        if let Some(&component) = annotations.component.as_ref().and_then(|c| c.get(i)) {
+            object.insert("component".to_string(), optional_index(component));
+        }
+        if let Some(&parent) = annotations.parent.as_ref().and_then(|p| p.get(i)) {
+            object.insert("parent".to_string(), optional_index(parent));
+        }
+        nodes.push(Value::Object(object));
+    }
+    nodes
+}
+
+/// Writes `g` in node-link format. Undirected edges are written once.
+pub fn write_node_link(g: &GraphList, annotations: &NodeAnnotations) -> String {
+    let mut links = vec![];
+    for node in &g.nodes {
+        for e in node.get_ordered_edge_list() {
+            if !g.undirected || e.from <= e.to {
+                links.push(json!({"source": e.from, "target": e.to, "weight": e.weight.0}));
+            }
+        }
+    }
+    let document = json!({
+        "directed": !g.undirected,
+        "nodes": node_objects(g, annotations),
+        "links": links,
+    });
+    serde_json::to_string_pretty(&document).unwrap()
+}
+
+/// Writes `g` in adjacency format, listing every neighbor of every node.
+pub fn write_adjacency(g: &GraphList, annotations: &NodeAnnotations) -> String {
+    let adjacency: Vec<Value> = g
+        .nodes
+        .iter()
+        .map(|node| {
+            node.get_ordered_edge_list()
+                .into_iter()
+                .map(|e| json!({"id": e.to, "weight": e.weight.0}))
+                .collect()
+        })
+        .collect();
+    let document = json!({
+        "directed": !g.undirected,
+        "nodes": node_objects(g, annotations),
+        "adjacency": adjacency,
+    });
+    serde_json::to_string_pretty(&document).unwrap()
+}
+
+// Node ids can be any JSON value, they are matched by their serialization.
+fn id_key(id: &Value) -> String {
+    id.to_string()
+}
+
+struct Nodes {
+    graph: GraphList,
+    annotations: NodeAnnotations,
+    ids: HashMap<String, usize>,
+}
+
+fn read_nodes(document: &Value) -> Result<Nodes, String> {
+    let object = document.as_object().ok_or("Expected a JSON object")?;
+    let directed = match object.get("directed") {
+        None => true,
+        Some(value) => value.as_bool().ok_or("`directed` must be a boolean")?,
+    };
+    let nodes = object
+        .get("nodes")
+        .and_then(Value::as_array)
+        .ok_or("Missing `nodes` array")?;
+    let mut graph = GraphList::with_nodes(!directed, 0);
+    let mut ids = HashMap::new();
+    let mut distance = vec![];
+    let mut component = vec![];
+    let mut parent = vec![];
+    let (mut has_distance, mut has_component, mut has_parent) = (false, false, false);
+    for (i, node) in nodes.iter().enumerate() {
+        let node = node
+            .as_object()
+            .ok_or(format!("Node {i} is not an object"))?;
+        let id = node.get("id").cloned().unwrap_or(json!(i));
+        let label = match (node.get("label"), &id) {
+            (Some(Value::String(label)), _) => Some(label.clone()),
+            (Some(Value::Null) | None, Value::String(id)) => Some(id.clone()),
+            (Some(Value::Null) | None, _) => None,
+            (Some(label), _) => Some(label.to_string()),
+        };
+        if ids.insert(id_key(&id), i).is_some() {
+            return Err(format!("Duplicate node id: {id}"));
+        }
+        graph.insert_node(label);
+
+        has_distance |= node.contains_key("distance");
+        distance.push(match node.get("distance") {
+            None | Some(Value::Null) => OrderedFloat(f64::INFINITY),
+            Some(d) => OrderedFloat(
+                d.as_f64()
+                    .ok_or(format!("Node {i}: `distance` must be a number"))?,
+            ),
+        });
+        for (key, has_key, values) in [
+            ("component", &mut has_component, &mut component),
+            ("parent", &mut has_parent, &mut parent),
+        ] {
+            *has_key |= node.contains_key(key);
+            values.push(match node.get(key) {
+                None | Some(Value::Null) => -1,
+                Some(v) => v
+                    .as_i64()
+                    .ok_or(format!("Node {i}: `{key}` must be an integer"))?
+                    as isize,
+            });
+        }
+    }
+    let annotations = NodeAnnotations {
+        distance: has_distance.then_some(distance),
+        component: has_component.then_some(component),
+        parent: has_parent.then_some(parent),
+    };
+    Ok(Nodes {
+        graph,
+        annotations,
+        ids,
+    })
+}
+
+// Link endpoints are node ids, or whole node objects once D3's force
+// simulation has replaced them.
+fn resolve(ids: &HashMap<String, usize>, endpoint: Option<&Value>) -> Result<usize, String> {
+    let endpoint = endpoint.ok_or("Link is missing an endpoint")?;
+    let id = match endpoint {
+        Value::Object(node) => node.get("id").ok_or("Linked node has no id")?,
+        id => id,
+    };
+    ids.get(&id_key(id))
+        .copied()
+        .ok_or(format!("Link refers to unknown node: {id}"))
+}
+
+// The `weight` of a link, or its `value` as in D3 examples.
+fn read_weight(object: &Map<String, Value>) -> Result<f64, String> {
+    let key = if object.contains_key("weight") {
+        "weight"
+    } else {
+        "value"
+    };
+    match object.get(key) {
+        None | Some(Value::Null) => Ok(1.0),
+        Some(w) => w.as_f64().ok_or(format!("`{key}` must be a number")),
+    }
+}
+
+/// Reads a node-link document. Nodes without an id are identified by their
+/// position, string ids are used as labels when no `label` is given, and
+/// `directed` defaults to true.
+pub fn read_node_link(input: &str) -> Result<(GraphList, NodeAnnotations), String> {
+    let document: Value = serde_json::from_str(input).map_err(|e| e.to_string())?;
+    let Nodes {
+        mut graph,
+        annotations,
+        ids,
+    } = read_nodes(&document)?;
+    let links = document
+        .get("links")
+        .or_else(|| document.get("edges"))
+        .and_then(Value::as_array)
+        .ok_or("Missing `links` array")?;
+    for (i, link) in links.iter().enumerate() {
+        let link = link
+            .as_object()
+            .ok_or(format!("Link {i} is not an object"))?;
+        let source = resolve(&ids, link.get("source"))?;
+        let target = resolve(&ids, link.get("target"))?;
+        graph.insert_edge(source, target, read_weight(link)?)?;
+    }
+    Ok((graph, annotations))
+}
+
+/// Reads an adjacency document.
+pub fn read_adjacency(input: &str) -> Result<(GraphList, NodeAnnotations), String> {
+    let document: Value = serde_json::from_str(input).map_err(|e| e.to_string())?;
+    let Nodes {
+        mut graph,
+        annotations,
+        ids,
+    } = read_nodes(&document)?;
+    let adjacency = document
+        .get("adjacency")
+        .and_then(Value::as_array)
+        .ok_or("Missing `adjacency` array")?;
+    if adjacency.len() != graph.num_nodes() {
+        return Err(format!(
+            "Expected {} adjacency lists, found {}",
+            graph.num_nodes(),
+            adjacency.len()
+        ));
+    }
+    for (from, neighbors) in adjacency.iter().enumerate() {
+        let neighbors = neighbors
+            .as_array()
+            .ok_or(format!("Adjacency list {from} is not an array"))?;
+        for neighbor in neighbors {
+            let neighbor = neighbor
+                .as_object()
+                .ok_or(format!("Neighbor of node {from} is not an object"))?;
+            let to = resolve(&ids, neighbor.get("id"))?;
+            graph.insert_edge(from, to, read_weight(neighbor)?)?;
+        }
+    }
+    Ok((graph, annotations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra::dijkstra;
+
+    fn create_test_graph() -> GraphList {
+        let mut graph = GraphList::with_nodes(false, 0);
+        graph.insert_node(Some("A".to_string()));
+        graph.insert_node(Some("B".to_string()));
+        graph.insert_node(None);
+        graph.insert_edge(0, 1, 2.0).unwrap();
+        graph.insert_edge(1, 2, 0.5).unwrap();
+        graph
+    }
+
+    fn assert_same_graph(a: &GraphList, b: &GraphList) {
+        assert_eq!(a.undirected, b.undirected);
+        assert_eq!(a.num_nodes(), b.num_nodes());
+        for i in 0..a.num_nodes() {
+            assert_eq!(a.nodes[i].label(), b.nodes[i].label());
+            for j in 0..a.num_nodes() {
+                assert_eq!(a.get_edge(i, j).unwrap(), b.get_edge(i, j).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_node_link_round_trip() {
+        let graph = create_test_graph();
+        let annotations = NodeAnnotations {
+            distance: Some(dijkstra(&graph, 1)),
+            component: Some(vec![0, 0, 0]),
+            parent: Some(vec![-1, -1, 1]),
+        };
+        let written = write_node_link(&graph, &annotations);
+        let (read, read_annotations) = read_node_link(&written).unwrap();
+        assert_same_graph(&graph, &read);
+        assert_eq!(read_annotations, annotations);
+    }
+
+    #[test]
+    fn test_node_link_output() {
+        let mut graph = create_test_graph();
+        graph.undirected = true;
+        let annotations = NodeAnnotations {
+            parent: Some(vec![-1, 0, 1]),
+            ..Default::default()
+        };
+        let document: Value = serde_json::from_str(&write_node_link(&graph, &annotations)).unwrap();
+        assert_eq!(document["directed"], json!(false));
+        assert_eq!(
+            document["nodes"][0],
+            json!({"id": 0, "label": "A", "parent": null})
+        );
+        assert_eq!(document["nodes"][2], json!({"id": 2, "parent": 1}));
+        assert_eq!(
+            document["links"][0],
+            json!({"source": 0, "target": 1, "weight": 2.0})
+        );
+    }
+
+    #[test]
+    fn test_infinite_distance_is_null() {
+        let graph = create_test_graph();
+        let annotations = NodeAnnotations {
+            distance: Some(dijkstra(&graph, 2)),
+            ..Default::default()
+        };
+        let written = write_node_link(&graph, &annotations);
+        let document: Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(document["nodes"][0]["distance"], Value::Null);
+        assert_eq!(document["nodes"][2]["distance"], json!(0.0));
+
+        let (_, read_annotations) = read_node_link(&written).unwrap();
+        assert_eq!(read_annotations.distance, annotations.distance);
+    }
+
+    #[test]
+    fn test_read_d3_style_node_link() {
+        let input = r#"{
+            "nodes": [{"id": "Myriel"}, {"id": "Napoleon", "group": 1}, {"id": "Mlle"}],
+            "links": [
+                {"source": "Napoleon", "target": "Myriel", "value": 2},
+                {"source": {"id": "Mlle"}, "target": {"id": "Myriel"}, "weight": 3}
+            ]
+        }"#;
+        let (graph, annotations) = read_node_link(input).unwrap();
+        assert!(!graph.undirected);
+        assert_eq!(graph.nodes[1].label(), Some("Napoleon"));
+        assert_eq!(
+            graph.get_edge(1, 0).unwrap().unwrap().weight,
+            OrderedFloat(2.0)
+        );
+        assert_eq!(
+            graph.get_edge(2, 0).unwrap().unwrap().weight,
+            OrderedFloat(3.0)
+        );
+        assert_eq!(annotations, NodeAnnotations::default());
+    }
+
+    #[test]
+    fn test_adjacency_round_trip() {
+        let mut graph = create_test_graph();
+        let written = write_adjacency(&graph, &NodeAnnotations::default());
+        let (read, _) = read_adjacency(&written).unwrap();
+        assert_same_graph(&graph, &read);
+
+        graph.undirected = true;
+        graph.insert_edge(2, 0, 4.0).unwrap();
+        let written = write_adjacency(&graph, &NodeAnnotations::default());
+        let (read, _) = read_adjacency(&written).unwrap();
+        assert!(read.is_edge(0, 2));
+        assert!(read.is_edge(2, 0));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read_node_link("not json").is_err());
+        assert!(read_node_link(r#"{"links": []}"#).is_err());
+        assert!(read_node_link(r#"{"nodes": [{"id": 1}, {"id": 1}], "links": []}"#).is_err());
+        assert!(
+            read_node_link(r#"{"nodes": [{"id": 0}], "links": [{"source": 0, "target": 5}]}"#)
+                .is_err()
+        );
+        assert!(read_adjacency(r#"{"nodes": [{"id": 0}], "adjacency": []}"#).is_err());
+        assert!(read_node_link(r#"{"nodes": [{"id": 0, "parent": "x"}], "links": []}"#).is_err());
+    }
+}
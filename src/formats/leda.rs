@@ -0,0 +1,205 @@
+use crate::graph::GraphList;
+use std::fmt::Write;
+
+// LEDA native graph files (`.gw`):
+//
+// LEDA.GRAPH
+// string          node info type, `void` when nodes carry nothing
+// double          edge info type
+// -1              -1 for directed graphs, -2 for undirected ones
+// 2               number of nodes, followed by one `|{info}|` per node
+// |{A}|
+// |{B}|
+// 1               number of edges, one `from to reversal |{info}|` each
+// 1 2 0 |{2.5}|
+//
+// Ids are 1-based and lines starting with `#` are comments. Node infos
+// become labels and numeric edge infos weights; undirected edges are listed once.
+
+// Extracts the text between `|{` and `}|`.
+fn info(text: &str) -> Option<&str> {
+    let text = text.trim_start().strip_prefix("|{")?;
+    Some(&text[..text.find("}|")?])
+}
+
+fn count((line, text): (usize, &str), what: &str) -> Result<usize, String> {
+    text.split_whitespace()
+        .next()
+        .and_then(|n| n.parse().ok())
+        .ok_or(format!("Line {line}: invalid {what}"))
+}
+
+pub fn read(input: &str) -> Result<GraphList, String> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+    let mut next = |what: &str| lines.next().ok_or(format!("Missing {what}"));
+
+    let (line, header) = next("header")?;
+    if header.split_whitespace().next() != Some("LEDA.GRAPH") {
+        return Err(format!("Line {line}: expected `LEDA.GRAPH`"));
+    }
+    next("node type")?;
+    let (_, edge_type) = next("edge type")?;
+    // Edge infos of other types, such as `string`, are not weights.
+    let numeric = matches!(
+        edge_type.split_whitespace().next(),
+        Some("int" | "long" | "short" | "float" | "double")
+    );
+    let (line, direction) = next("direction")?;
+    let undirected = match direction.split_whitespace().next() {
+        Some("-1") => false,
+        Some("-2") => true,
+        _ => return Err(format!("Line {line}: expected -1 or -2")),
+    };
+
+    let num_nodes = count(next("number of nodes")?, "number of nodes")?;
+    let mut g = GraphList::with_nodes(undirected, 0);
+    for _ in 0..num_nodes {
+        let (line, text) = next("node")?;
+        let label = info(text).ok_or(format!("Line {line}: expected `|{{...}}|`"))?;
+        g.insert_node((!label.is_empty()).then(|| label.to_string()));
+    }
+
+    let num_edges = count(next("number of edges")?, "number of edges")?;
+    for _ in 0..num_edges {
+        let (line, text) = next("edge")?;
+        let tokens: Vec<&str> = text.splitn(4, char::is_whitespace).collect();
+        if tokens.len() < 3 {
+            return Err(format!(
+                "Line {line}: expected `from to reversal |{{...}}|`"
+            ));
+        }
+        let node = |token: &str| match token.parse::<usize>() {
+            Ok(id) if id >= 1 && id <= num_nodes => Ok(id - 1),
+            _ => Err(format!("Line {line}: invalid node `{token}`")),
+        };
+        let (from, to) = (node(tokens[0])?, node(tokens[1])?);
+        let weight = info(tokens.get(3).unwrap_or(&""))
+            .ok_or(format!("Line {line}: expected `|{{...}}|`"))?;
+        let weight = if weight.is_empty() || !numeric {
+            1.0
+        } else {
+            weight
+                .parse()
+                .map_err(|_| format!("Line {line}: edge info is not a number: {weight}"))?
+        };
+        g.insert_edge(from, to, weight)?;
+    }
+    Ok(g)
+}
+
+/// Writes `g` with `string` node infos (or `void` when no node has a label)
+/// and `double` edge infos. Fails on labels containing `}|`, which cannot
+/// be represented.
+pub fn write(g: &GraphList) -> Result<String, String> {
+    let labelled = g.nodes.iter().any(|node| node.label().is_some());
+    let mut out = String::from("LEDA.GRAPH\n");
+    out.push_str(if labelled { "string\n" } else { "void\n" });
+    out.push_str("double\n");
+    out.push_str(if g.undirected { "-2\n" } else { "-1\n" });
+    writeln!(out, "{}", g.num_nodes()).unwrap();
+    for node in &g.nodes {
+        let label = node.label().unwrap_or("");
+        if label.contains("}|") {
+            return Err(format!("Label of node {} contains `}}|`", node.index()));
+        }
+        writeln!(out, "|{{{label}}}|").unwrap();
+    }
+    let edges: Vec<_> = g
+        .make_edge_list()
+        .into_iter()
+        .filter(|e| !g.undirected || e.from <= e.to)
+        .collect();
+    writeln!(out, "{}", edges.len()).unwrap();
+    for e in edges {
+        writeln!(out, "{} {} 0 |{{{}}}|", e.from + 1, e.to + 1, e.weight).unwrap();
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    #[test]
+    fn test_read() {
+        let input = "# header
+LEDA.GRAPH
+string
+int
+-1
+# nodes section
+3
+|{v1}|
+|{}|
+|{with spaces}|
+# edges section
+3
+1 2 0 |{4}|
+2 3 0 |{-2}|
+3 3 0 |{}|
+";
+        let g = read(input).unwrap();
+        assert!(!g.undirected);
+        assert_eq!(g.nodes[0].label(), Some("v1"));
+        assert_eq!(g.nodes[1].label(), None);
+        assert_eq!(g.nodes[2].label(), Some("with spaces"));
+        assert_eq!(g.get_edge(0, 1).unwrap().unwrap().weight, OrderedFloat(4.0));
+        assert_eq!(
+            g.get_edge(1, 2).unwrap().unwrap().weight,
+            OrderedFloat(-2.0)
+        );
+        assert_eq!(g.get_edge(2, 2).unwrap().unwrap().weight, OrderedFloat(1.0));
+        assert!(!g.is_edge(1, 0));
+    }
+
+    #[test]
+    fn test_read_void_and_undirected() {
+        let input = "LEDA.GRAPH\nvoid\nstring\n-2\n2\n|{}|\n|{}|\n1\n1 2 0 |{road}|\n";
+        let g = read(input).unwrap();
+        assert!(g.undirected);
+        assert_eq!(g.get_edge(1, 0).unwrap().unwrap().weight, OrderedFloat(1.0));
+        // Only numeric edge infos are read as weights.
+        assert!(read(&input.replace("void\nstring", "void\nint")).is_err());
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let input = "LEDA.GRAPH\nvoid\nvoid\n-1\n2\n|{}|\n|{}|\n1\n1 2 0 |{}|\n";
+        assert!(read(input).is_ok());
+        assert!(read(&input.replace("LEDA.GRAPH", "GRAPH")).is_err());
+        assert!(read(&input.replace("-1", "1")).is_err());
+        assert!(read(&input.replace("1 2 0", "1 3 0")).is_err());
+        assert!(read(&input.replace("1 2 0 |{}|", "1 2")).is_err());
+        assert!(read(&input.replace("\n1\n", "\n2\n")).is_err());
+        assert!(read(&input.replace("|{}|\n|{}|\n1", "|{}|\n1")).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for undirected in [false, true] {
+            let mut graph = GraphList::with_nodes(undirected, 0);
+            graph.insert_node(Some("first node".to_string()));
+            graph.insert_node(None);
+            graph.insert_node(Some("C".to_string()));
+            graph.insert_edge(0, 1, 2.0).unwrap();
+            graph.insert_edge(2, 0, -0.5).unwrap();
+            graph.insert_edge(1, 1, 1.0).unwrap();
+            let read = read(&write(&graph).unwrap()).unwrap();
+            assert_eq!(read.undirected, undirected);
+            for i in 0..3 {
+                assert_eq!(read.nodes[i].label(), graph.nodes[i].label());
+                for j in 0..3 {
+                    assert_eq!(read.get_edge(i, j).unwrap(), graph.get_edge(i, j).unwrap());
+                }
+            }
+        }
+        let mut graph = GraphList::with_nodes(true, 0);
+        graph.insert_node(Some("a}|b".to_string()));
+        assert!(write(&graph).is_err());
+    }
+}
@@ -0,0 +1,426 @@
+use crate::graph::{GraphList, GraphMatrix};
+use ordered_float::OrderedFloat;
+use std::fmt::Write;
+
+// Matrix Market (.mtx) files, as described in
+// https://math.nist.gov/MatrixMarket/formats.html
+// Row `i` and column `j` of the matrix are read as an edge `i -> j`.
+// Symmetric matrices become undirected graphs.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Layout {
+    Coordinate,
+    Array,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Field {
+    Real,
+    Integer,
+    Pattern,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Symmetry {
+    General,
+    Symmetric,
+    SkewSymmetric,
+}
+
+struct Matrix {
+    size: usize,
+    layout: Layout,
+    symmetry: Symmetry,
+    // 0-based (row, column, value), exactly as stored in the file.
+    entries: Vec<(usize, usize, f64)>,
+}
+
+fn parse_header(line: &str) -> Result<(Layout, Field, Symmetry), String> {
+    let words: Vec<String> = line.split_whitespace().map(|w| w.to_lowercase()).collect();
+    if words.len() != 5 || words[0] != "%%matrixmarket" {
+        return Err(format!("Invalid Matrix Market header: {line}"));
+    }
+    if words[1] != "matrix" {
+        return Err(format!("Unsupported Matrix Market object: {}", words[1]));
+    }
+    let layout = match words[2].as_str() {
+        "coordinate" => Layout::Coordinate,
+        "array" => Layout::Array,
+        other => return Err(format!("Unsupported Matrix Market format: {other}")),
+    };
+    let field = match words[3].as_str() {
+        "real" | "double" => Field::Real,
+        "integer" => Field::Integer,
+        "pattern" if layout == Layout::Coordinate => Field::Pattern,
+        other => return Err(format!("Unsupported Matrix Market field: {other}")),
+    };
+    let symmetry = match words[4].as_str() {
+        "general" => Symmetry::General,
+        "symmetric" => Symmetry::Symmetric,
+        "skew-symmetric" => Symmetry::SkewSymmetric,
+        other => return Err(format!("Unsupported Matrix Market symmetry: {other}")),
+    };
+    Ok((layout, field, symmetry))
+}
+
+fn parse_number<T: std::str::FromStr>(token: &str, line: usize) -> Result<T, String> {
+    token
+        .parse()
+        .map_err(|_| format!("Line {line}: invalid number: {token}"))
+}
+
+fn parse_value(token: &str, field: Field, line: usize) -> Result<f64, String> {
+    match field {
+        Field::Integer => parse_number::<i64>(token, line).map(|v| v as f64),
+        _ => parse_number(token, line),
+    }
+}
+
+fn parse(input: &str) -> Result<Matrix, String> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()));
+    let (_, header) = lines.next().ok_or("Empty Matrix Market file")?;
+    let (layout, field, symmetry) = parse_header(header)?;
+    let mut lines = lines.filter(|(_, line)| !line.is_empty() && !line.starts_with('%'));
+
+    let (size_line, size) = lines.next().ok_or("Missing Matrix Market size line")?;
+    let size: Vec<usize> = size
+        .split_whitespace()
+        .map(|t| parse_number(t, size_line))
+        .collect::<Result<_, _>>()?;
+    let expected_sizes = if layout == Layout::Coordinate { 3 } else { 2 };
+    if size.len() != expected_sizes {
+        return Err(format!("Line {size_line}: invalid size line"));
+    }
+    let (rows, cols) = (size[0], size[1]);
+    if rows != cols {
+        return Err(format!(
+            "Adjacency matrix must be square, got {rows}x{cols}"
+        ));
+    }
+    if symmetry == Symmetry::SkewSymmetric && field == Field::Pattern {
+        return Err("Skew-symmetric pattern matrices are not supported".to_string());
+    }
+
+    let mut entries = vec![];
+    match layout {
+        Layout::Coordinate => {
+            let nnz = size[2];
+            for (line, content) in lines.by_ref().take(nnz) {
+                let tokens: Vec<&str> = content.split_whitespace().collect();
+                let expected = if field == Field::Pattern { 2 } else { 3 };
+                if tokens.len() != expected {
+                    return Err(format!("Line {line}: expected {expected} values"));
+                }
+                let i: usize = parse_number(tokens[0], line)?;
+                let j: usize = parse_number(tokens[1], line)?;
+                if !(1..=rows).contains(&i) || !(1..=cols).contains(&j) {
+                    return Err(format!("Line {line}: entry ({i}, {j}) out of range"));
+                }
+                if symmetry != Symmetry::General && j > i {
+                    return Err(format!(
+                        "Line {line}: entry ({i}, {j}) above the diagonal of a symmetric matrix"
+                    ));
+                }
+                if symmetry == Symmetry::SkewSymmetric && j == i {
+                    return Err(format!(
+                        "Line {line}: entry ({i}, {j}) on the diagonal of a skew-symmetric matrix"
+                    ));
+                }
+                let value = match field {
+                    Field::Pattern => 1.0,
+                    _ => parse_value(tokens[2], field, line)?,
+                };
+                entries.push((i - 1, j - 1, value));
+            }
+            if entries.len() != nnz {
+                return Err(format!("Expected {nnz} entries, found {}", entries.len()));
+            }
+        }
+        Layout::Array => {
+            // Values are listed in column-major order; symmetric matrices
+            // only store the lower triangle. Positions are generated as the
+            // values are read, since the size may not fit in memory.
+            let first_row = move |j: usize| match symmetry {
+                Symmetry::General => 0,
+                Symmetry::Symmetric => j,
+                Symmetry::SkewSymmetric => j + 1,
+            };
+            let n = rows as u128;
+            let expected = match symmetry {
+                Symmetry::General => n * n,
+                Symmetry::Symmetric => n * (n + 1) / 2,
+                Symmetry::SkewSymmetric => n * n.saturating_sub(1) / 2,
+            };
+            let mut positions =
+                (0..cols).flat_map(move |j| (first_row(j)..rows).map(move |i| (i, j)));
+            for (line, content) in lines.by_ref() {
+                for token in content.split_whitespace() {
+                    let (i, j) = positions
+                        .next()
+                        .ok_or(format!("Expected {expected} values, found more"))?;
+                    entries.push((i, j, parse_value(token, field, line)?));
+                }
+            }
+            if positions.next().is_some() {
+                return Err(format!("Expected {expected} values, found fewer"));
+            }
+        }
+    }
+    if let Some((line, _)) = lines.next() {
+        return Err(format!("Line {line}: unexpected data after the last entry"));
+    }
+    Ok(Matrix {
+        size: rows,
+        layout,
+        symmetry,
+        entries,
+    })
+}
+
+/// Reads a Matrix Market file into a `GraphList`. Explicit zeros in
+/// coordinate files are kept as zero-weight edges, zeros in array files
+/// mean there is no edge.
+pub fn read_graph_list(input: &str) -> Result<GraphList, String> {
+    let matrix = parse(input)?;
+    let mut g = GraphList::try_with_nodes(matrix.symmetry == Symmetry::Symmetric, matrix.size)?;
+    for (i, j, value) in matrix.entries {
+        if matrix.layout == Layout::Array && value == 0.0 {
+            continue;
+        }
+        g.insert_edge(i, j, value)?;
+        if matrix.symmetry == Symmetry::SkewSymmetric {
+            g.insert_edge(j, i, -value)?;
+        }
+    }
+    Ok(g)
+}
+
+/// Reads a Matrix Market file into a dense `GraphMatrix`, failing if the
+/// matrix is not `N`x`N`.
+pub fn read_graph_matrix<const N: usize>(input: &str) -> Result<GraphMatrix<N>, String> {
+    let matrix = parse(input)?;
+    if matrix.size != N {
+        return Err(format!("Matrix has {} nodes, expected {N}", matrix.size));
+    }
+    let mut m = GraphMatrix::new(matrix.symmetry == Symmetry::Symmetric);
+    for (i, j, value) in matrix.entries {
+        m.set_edge(i, j, value.into())?;
+        match matrix.symmetry {
+            Symmetry::General => {}
+            Symmetry::Symmetric => m.set_edge(j, i, value.into())?,
+            Symmetry::SkewSymmetric => m.set_edge(j, i, (-value).into())?,
+        }
+    }
+    Ok(m)
+}
+
+/// Writes a `GraphList` as a coordinate file. Undirected graphs are written
+/// as symmetric matrices, keeping only the lower triangle.
+pub fn write_graph_list(g: &GraphList) -> String {
+    let symmetry = if g.undirected { "symmetric" } else { "general" };
+    let mut entries = vec![];
+    for node in &g.nodes {
+        for e in node.get_ordered_edge_list() {
+            if !g.undirected || e.to <= e.from {
+                entries.push(e);
+            }
+        }
+    }
+    let n = g.num_nodes();
+    let mut out = format!("%%MatrixMarket matrix coordinate real {symmetry}\n");
+    writeln!(out, "{n} {n} {}", entries.len()).unwrap();
+    for e in entries {
+        writeln!(out, "{} {} {}", e.from + 1, e.to + 1, e.weight).unwrap();
+    }
+    out
+}
+
+/// Writes a `GraphMatrix` as a dense array file.
+pub fn write_graph_matrix<const N: usize>(m: &GraphMatrix<N>) -> String {
+    let symmetry = if m.is_undirected() {
+        "symmetric"
+    } else {
+        "general"
+    };
+    let mut out = format!("%%MatrixMarket matrix array real {symmetry}\n");
+    writeln!(out, "{N} {N}").unwrap();
+    for j in 0..N {
+        let first_row = if m.is_undirected() { j } else { 0 };
+        for i in first_row..N {
+            let OrderedFloat(weight) = m.get_edge(i, j).unwrap();
+            writeln!(out, "{weight}").unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_coordinate_general() {
+        let input = "%%MatrixMarket matrix coordinate real general
+% a comment
+3 3 3
+1 2 1.5
+2 3 2
+3 1 -1
+";
+        let g = read_graph_list(input).unwrap();
+        assert!(!g.undirected);
+        assert_eq!(g.num_nodes(), 3);
+        assert_eq!(g.get_edge(0, 1).unwrap().unwrap().weight, OrderedFloat(1.5));
+        assert_eq!(g.get_edge(1, 2).unwrap().unwrap().weight, OrderedFloat(2.0));
+        assert_eq!(
+            g.get_edge(2, 0).unwrap().unwrap().weight,
+            OrderedFloat(-1.0)
+        );
+        assert!(!g.is_edge(1, 0));
+    }
+
+    #[test]
+    fn test_read_coordinate_symmetric_pattern() {
+        let input = "%%MatrixMarket matrix coordinate pattern symmetric
+3 3 2
+2 1
+3 2
+";
+        let g = read_graph_list(input).unwrap();
+        assert!(g.undirected);
+        assert!(g.is_edge(0, 1));
+        assert!(g.is_edge(1, 0));
+        assert!(g.is_edge(2, 1));
+        assert_eq!(g.get_edge(1, 2).unwrap().unwrap().weight, OrderedFloat(1.0));
+        assert!(!g.is_edge(0, 2));
+    }
+
+    #[test]
+    fn test_read_array_symmetric_into_matrix() {
+        // Lower triangle in column-major order:
+        // 0 1 2
+        // 1 0 3
+        // 2 3 0
+        let input = "%%MatrixMarket matrix array integer symmetric
+3 3
+0
+1
+2
+0
+3
+0
+";
+        let m: GraphMatrix<3> = read_graph_matrix(input).unwrap();
+        assert!(m.is_undirected());
+        assert_eq!(m.get_edge(0, 1), Some(OrderedFloat(1.0)));
+        assert_eq!(m.get_edge(1, 0), Some(OrderedFloat(1.0)));
+        assert_eq!(m.get_edge(2, 0), Some(OrderedFloat(2.0)));
+        assert_eq!(m.get_edge(1, 2), Some(OrderedFloat(3.0)));
+
+        let g = read_graph_list(input).unwrap();
+        assert!(!g.is_edge(0, 0));
+        assert!(g.is_edge(0, 2));
+    }
+
+    #[test]
+    fn test_read_skew_symmetric() {
+        let input = "%%MatrixMarket matrix coordinate real skew-symmetric
+2 2 1
+2 1 4
+";
+        let g = read_graph_list(input).unwrap();
+        assert!(!g.undirected);
+        assert_eq!(g.get_edge(1, 0).unwrap().unwrap().weight, OrderedFloat(4.0));
+        assert_eq!(
+            g.get_edge(0, 1).unwrap().unwrap().weight,
+            OrderedFloat(-4.0)
+        );
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read_graph_list("").is_err());
+        assert!(
+            read_graph_list("%%MatrixMarket matrix coordinate complex general\n1 1 0\n").is_err()
+        );
+        // Not square
+        assert!(read_graph_list("%%MatrixMarket matrix coordinate real general\n2 3 0\n").is_err());
+        // Entry out of range
+        assert!(
+            read_graph_list("%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1\n")
+                .is_err()
+        );
+        // Missing entries
+        assert!(
+            read_graph_list("%%MatrixMarket matrix coordinate real general\n2 2 2\n1 2 1\n")
+                .is_err()
+        );
+        // Upper triangle in a symmetric file
+        assert!(
+            read_graph_list("%%MatrixMarket matrix coordinate real symmetric\n2 2 1\n1 2 1\n")
+                .is_err()
+        );
+        // Diagonal of a skew-symmetric file, which must be zero
+        assert_eq!(
+            read_graph_list("%%MatrixMarket matrix coordinate real skew-symmetric\n2 2 1\n2 2 5\n")
+                .unwrap_err(),
+            "Line 3: entry (2, 2) on the diagonal of a skew-symmetric matrix"
+        );
+        // Sizes too large to allocate
+        assert_eq!(
+            read_graph_list(
+                "%%MatrixMarket matrix coordinate real general\n\
+                 18446744073709551615 18446744073709551615 0\n"
+            )
+            .unwrap_err(),
+            "Not enough memory for 18446744073709551615 nodes"
+        );
+        assert_eq!(
+            read_graph_list(
+                "%%MatrixMarket matrix array real general\n18446744073709551615 18446744073709551615\n1\n"
+            )
+            .unwrap_err(),
+            "Expected 340282366920938463426481119284349108225 values, found fewer"
+        );
+        // Wrong size for a GraphMatrix
+        assert!(read_graph_matrix::<2>(
+            "%%MatrixMarket matrix array real general\n3 3\n0\n0\n0\n0\n0\n0\n0\n0\n0\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_graph_list_round_trip() {
+        let mut g = GraphList::with_nodes(true, 4);
+        g.insert_edge(0, 1, 2.5).unwrap();
+        g.insert_edge(1, 2, 1.0).unwrap();
+        g.insert_edge(3, 3, 7.0).unwrap();
+        let written = write_graph_list(&g);
+        assert!(written.starts_with("%%MatrixMarket matrix coordinate real symmetric\n4 4 3\n"));
+
+        let read = read_graph_list(&written).unwrap();
+        assert!(read.undirected);
+        for i in 0..4 {
+            for j in 0..4 {
+                assert_eq!(read.get_edge(i, j).unwrap(), g.get_edge(i, j).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_graph_matrix_round_trip() {
+        let mut m: GraphMatrix<3> = GraphMatrix::new(false);
+        m.set_edge(0, 1, OrderedFloat(2.0)).unwrap();
+        m.set_edge(2, 0, OrderedFloat(-3.5)).unwrap();
+        let read: GraphMatrix<3> = read_graph_matrix(&write_graph_matrix(&m)).unwrap();
+        assert!(!read.is_undirected());
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_eq!(read.get_edge(i, j), m.get_edge(i, j));
+            }
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use crate::graph::GraphList;
+use std::fmt::Write;
+
+// METIS graph files, as described in section 4.1.1 of the METIS manual.
+// The header is `n m [fmt [ncon]]`, where the digits of `fmt` flag vertex
+// sizes, vertex weights and edge weights. It is followed by one line per
+// vertex with its size, its `ncon` weights and its 1-based neighbors, each
+// followed by the edge weight when edge weights are present. Lines starting
+// with `%` are comments. METIS graphs are always undirected.
+
+/// A METIS graph with the vertex data `GraphList` has no place for.
+#[derive(Clone, Debug)]
+pub struct MetisGraph {
+    pub graph: GraphList,
+    pub vertex_sizes: Option<Vec<i64>>,
+    /// `ncon` weights per vertex.
+    pub vertex_weights: Option<Vec<Vec<i64>>>,
+}
+
+impl From<GraphList> for MetisGraph {
+    fn from(graph: GraphList) -> Self {
+        MetisGraph {
+            graph,
+            vertex_sizes: None,
+            vertex_weights: None,
+        }
+    }
+}
+
+fn parse_int<T: std::str::FromStr>(token: &str, line: usize) -> Result<T, String> {
+    token
+        .parse()
+        .map_err(|_| format!("Line {line}: invalid integer: {token}"))
+}
+
+pub fn read(input: &str) -> Result<MetisGraph, String> {
+    // Empty lines are vertices without neighbors, only comments are skipped.
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.starts_with('%'));
+    let (header_line, header) = lines
+        .by_ref()
+        .find(|(_, line)| !line.is_empty())
+        .ok_or("Missing METIS header")?;
+    let header: Vec<&str> = header.split_whitespace().collect();
+    if header.len() < 2 || header.len() > 4 {
+        return Err(format!("Line {header_line}: invalid METIS header"));
+    }
+    let n: usize = parse_int(header[0], header_line)?;
+    let m: usize = parse_int(header[1], header_line)?;
+    let fmt = header.get(2).copied().unwrap_or("0");
+    if fmt.len() > 3 || fmt.chars().any(|c| c != '0' && c != '1') {
+        return Err(format!("Line {header_line}: invalid fmt: {fmt}"));
+    }
+    let fmt = format!("{fmt:0>3}");
+    let has_sizes = &fmt[0..1] == "1";
+    let has_vertex_weights = &fmt[1..2] == "1";
+    let has_edge_weights = &fmt[2..3] == "1";
+    let ncon = match header.get(3) {
+        Some(ncon) if has_vertex_weights => parse_int(ncon, header_line)?,
+        Some(_) => {
+            return Err(format!(
+                "Line {header_line}: ncon given without vertex weights"
+            ))
+        }
+        None => 1,
+    };
+
+    // Every vertex has a line, so a count beyond the lines left is wrong
+    // and must not be allocated.
+    let available = lines.clone().count();
+    if n > available {
+        return Err(format!("Expected {n} vertex lines, found {available}"));
+    }
+    let mut graph = GraphList::with_nodes(true, n);
+    let mut vertex_sizes = vec![];
+    let mut vertex_weights = vec![];
+    let mut arcs = 0;
+    // Line and number of neighbors listed for each vertex.
+    let mut listed = vec![];
+    for from in 0..n {
+        let (line, content) = lines
+            .next()
+            .ok_or(format!("Expected {n} vertex lines, found {from}"))?;
+        let mut tokens = content.split_whitespace();
+        let mut next_int = |what: &str| -> Result<i64, String> {
+            let token = tokens
+                .next()
+                .ok_or(format!("Line {line}: missing {what}"))?;
+            parse_int(token, line)
+        };
+        if has_sizes {
+            vertex_sizes.push(next_int("vertex size")?);
+        }
+        if has_vertex_weights {
+            let weights = (0..ncon)
+                .map(|_| next_int("vertex weight"))
+                .collect::<Result<_, _>>()?;
+            vertex_weights.push(weights);
+        }
+        let rest: Vec<i64> = tokens
+            .map(|token| parse_int(token, line))
+            .collect::<Result<_, _>>()?;
+        let step = if has_edge_weights { 2 } else { 1 };
+        if !rest.len().is_multiple_of(step) {
+            return Err(format!("Line {line}: neighbor without an edge weight"));
+        }
+        listed.push((line, rest.len() / step));
+        for pair in rest.chunks(step) {
+            let to = pair[0];
+            if to < 1 || to as usize > n {
+                return Err(format!("Line {line}: neighbor {to} out of range"));
+            }
+            let to = to as usize - 1;
+            if to == from {
+                return Err(format!("Line {line}: self-loops are not allowed"));
+            }
+            let weight = if has_edge_weights {
+                pair[1] as f64
+            } else {
+                1.0
+            };
+            if let Some(existing) = graph.nodes[from].get_edge(to) {
+                if existing.weight.0 != weight {
+                    return Err(format!(
+                        "Line {line}: edge {} - {} has different weights in each direction",
+                        from + 1,
+                        to + 1
+                    ));
+                }
+            }
+            graph.insert_edge(from, to, weight)?;
+            arcs += 1;
+        }
+    }
+    if let Some((line, _)) = lines.find(|(_, line)| !line.is_empty()) {
+        return Err(format!("Line {line}: more than {n} vertex lines"));
+    }
+    // Each vertex must list exactly the neighbors that list it, once each.
+    for (node, &(line, count)) in graph.nodes.iter().zip(&listed) {
+        if node.num_edges() != count {
+            return Err(format!(
+                "Line {line}: vertex {} must list each of its {} neighbors once",
+                node.index() + 1,
+                node.num_edges()
+            ));
+        }
+    }
+    let stored: usize = graph.nodes.iter().map(|node| node.num_edges()).sum();
+    let declared = m.checked_mul(2);
+    if declared != Some(arcs) || declared != Some(stored) {
+        return Err(format!(
+            "Header declares {m} edges, but the adjacency lists describe {} edges",
+            stored as f64 / 2.0
+        ));
+    }
+    Ok(MetisGraph {
+        graph,
+        vertex_sizes: has_sizes.then_some(vertex_sizes),
+        vertex_weights: has_vertex_weights.then_some(vertex_weights),
+    })
+}
+
+/// Writes a METIS file. The graph must be undirected and without
+/// self-loops, and edge weights, when not all 1, must be integers.
+pub fn write(metis: &MetisGraph) -> Result<String, String> {
+    let g = &metis.graph;
+    if !g.undirected {
+        return Err("METIS graphs must be undirected".to_string());
+    }
+    let n = g.num_nodes();
+    let edges = g.make_edge_list();
+    if let Some(e) = edges.iter().find(|e| e.from == e.to) {
+        return Err(format!(
+            "METIS graphs cannot have self-loops: node {}",
+            e.from
+        ));
+    }
+    if let Some(e) = edges.iter().find(|e| e.weight.fract() != 0.0) {
+        return Err(format!(
+            "METIS edge weights must be integers: {} - {} has weight {}",
+            e.from, e.to, e.weight
+        ));
+    }
+    let has_edge_weights = edges.iter().any(|e| e.weight.0 != 1.0);
+    if let Some(sizes) = &metis.vertex_sizes {
+        if sizes.len() != n {
+            return Err(format!("Expected {n} vertex sizes, found {}", sizes.len()));
+        }
+    }
+    let mut ncon = 0;
+    if let Some(weights) = &metis.vertex_weights {
+        if weights.len() != n {
+            return Err(format!(
+                "Expected {n} vertex weights, found {}",
+                weights.len()
+            ));
+        }
+        ncon = weights.first().map_or(1, Vec::len);
+        if ncon == 0 || weights.iter().any(|w| w.len() != ncon) {
+            return Err("Every vertex needs the same, non-zero number of weights".to_string());
+        }
+    }
+
+    let flag = |present: bool| if present { '1' } else { '0' };
+    let fmt: String = [
+        flag(metis.vertex_sizes.is_some()),
+        flag(metis.vertex_weights.is_some()),
+        flag(has_edge_weights),
+    ]
+    .iter()
+    .collect();
+    let mut out = format!("{n} {}", edges.len() / 2);
+    if fmt != "000" {
+        write!(out, " {fmt}").unwrap();
+    }
+    if ncon > 1 {
+        write!(out, " {ncon}").unwrap();
+    }
+    out.push('\n');
+    for node in &g.nodes {
+        let mut tokens = vec![];
+        if let Some(sizes) = &metis.vertex_sizes {
+            tokens.push(sizes[node.index()].to_string());
+        }
+        if let Some(weights) = &metis.vertex_weights {
+            tokens.extend(weights[node.index()].iter().map(i64::to_string));
+        }
+        for e in node.get_ordered_edge_list() {
+            tokens.push((e.to + 1).to_string());
+            if has_edge_weights {
+                tokens.push((e.weight.0 as i64).to_string());
+            }
+        }
+        writeln!(out, "{}", tokens.join(" ")).unwrap();
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    #[test]
+    fn test_read_unweighted() {
+        // Example from the METIS manual, figure 2a.
+        let input = "% comment
+7 11
+5 3 2
+1 3 4
+5 4 2 1
+2 3 6 7
+1 3 6
+5 4 7
+6 4
+";
+        let metis = read(input).unwrap();
+        let g = &metis.graph;
+        assert!(g.undirected);
+        assert_eq!(g.num_nodes(), 7);
+        assert_eq!(g.make_edge_list().len(), 22);
+        assert!(g.is_edge(0, 4));
+        assert!(g.is_edge(6, 3));
+        assert!(metis.vertex_weights.is_none());
+        assert!(metis.vertex_sizes.is_none());
+    }
+
+    #[test]
+    fn test_read_weighted() {
+        // Two vertex weights per vertex, edges 1-2 (3), 1-3 (5), 2-3 (2), 3-4 (1).
+        let input = "4 4 011 2
+1 2 2 3 3 5
+0 1 1 3 3 2
+2 2 1 5 2 2 4 1
+4 1 3 1
+";
+        let metis = read(input).unwrap();
+        assert_eq!(metis.vertex_weights.as_ref().unwrap()[0], vec![1, 2]);
+        assert_eq!(metis.vertex_weights.as_ref().unwrap()[3], vec![4, 1]);
+        let g = &metis.graph;
+        assert_eq!(g.get_edge(0, 2).unwrap().unwrap().weight, OrderedFloat(5.0));
+        assert_eq!(g.get_edge(2, 0).unwrap().unwrap().weight, OrderedFloat(5.0));
+        assert_eq!(g.get_edge(3, 2).unwrap().unwrap().weight, OrderedFloat(1.0));
+    }
+
+    #[test]
+    fn test_isolated_vertex_and_sizes() {
+        let input = "3 1 100\n4 2\n1 1\n7\n";
+        let metis = read(input).unwrap();
+        assert_eq!(metis.vertex_sizes, Some(vec![4, 1, 7]));
+        assert_eq!(metis.graph.nodes[2].num_edges(), 0);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("").is_err());
+        // Wrong edge count
+        assert!(read("2 2\n2\n1\n").is_err());
+        // Asymmetric adjacency
+        assert!(read("3 1\n2\n\n\n").is_err());
+        assert_eq!(
+            read("2 1\n2 2\n\n").unwrap_err(),
+            "Line 2: vertex 1 must list each of its 1 neighbors once"
+        );
+        // Neighbor out of range
+        assert!(read("2 1\n3\n1\n").is_err());
+        // Self-loop
+        assert!(read("1 1\n1\n").is_err());
+        // Different weights in each direction
+        assert!(read("2 1 1\n2 3\n1 4\n").is_err());
+        // Too many vertex lines
+        assert!(read("1 0\n\n1\n").is_err());
+        // Negative counts
+        assert_eq!(read("-1 0").unwrap_err(), "Line 1: invalid integer: -1");
+        assert_eq!(
+            read("2 -1\n2\n1\n").unwrap_err(),
+            "Line 1: invalid integer: -1"
+        );
+        // Counts beyond the file
+        assert_eq!(
+            read("18446744073709551615 0\n").unwrap_err(),
+            "Expected 18446744073709551615 vertex lines, found 0"
+        );
+        assert!(read("2 9223372036854775808\n2\n1\n").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut graph = GraphList::with_nodes(true, 4);
+        graph.insert_edge(0, 1, 3.0).unwrap();
+        graph.insert_edge(1, 2, 1.0).unwrap();
+        graph.insert_edge(3, 0, 2.0).unwrap();
+        let metis = MetisGraph {
+            graph,
+            vertex_sizes: None,
+            vertex_weights: Some(vec![vec![1, 2], vec![3, 4], vec![5, 6], vec![7, 8]]),
+        };
+        let written = write(&metis).unwrap();
+        assert!(written.starts_with("4 3 011 2\n1 2 2 3 4 2\n"));
+        let read = read(&written).unwrap();
+        assert_eq!(read.vertex_weights, metis.vertex_weights);
+        for i in 0..4 {
+            for j in 0..4 {
+                assert_eq!(
+                    read.graph.get_edge(i, j).unwrap(),
+                    metis.graph.get_edge(i, j).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_errors() {
+        let mut directed = GraphList::with_nodes(false, 2);
+        directed.insert_edge(0, 1, 1.0).unwrap();
+        assert!(write(&directed.into()).is_err());
+
+        let mut fractional = GraphList::with_nodes(true, 2);
+        fractional.insert_edge(0, 1, 1.5).unwrap();
+        assert!(write(&fractional.into()).is_err());
+
+        let mut self_loop = GraphList::with_nodes(true, 1);
+        self_loop.insert_edge(0, 0, 1.0).unwrap();
+        assert!(write(&self_loop.into()).is_err());
+    }
+}
@@ -0,0 +1,19 @@
+pub mod binary;
+pub mod csv;
+pub mod diagram;
+pub mod dimacs;
+pub mod dot;
+pub mod file;
+pub mod gexf;
+pub mod gml;
+pub mod graphml;
+pub mod json;
+pub mod leda;
+pub mod matrix_market;
+pub mod metis;
+pub mod pajek;
+pub mod plain;
+pub mod snap;
+pub mod svg;
+pub mod tsplib;
+mod xml;
@@ -0,0 +1,241 @@
+use crate::graph::GraphList;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// Pajek `.net` files: a `*Vertices n` section with one `id "label"` line
+// per vertex, followed by any number of `*Arcs` (directed) and `*Edges`
+// (undirected) sections with `from to [weight]` lines, or their list forms
+// `*Arcslist` / `*Edgeslist` with `from to1 to2 ...` lines. Ids are 1-based,
+// keywords are case insensitive and `%` starts a comment line. Vertex
+// coordinates and drawing attributes are ignored.
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Vertices,
+    Arcs,
+    Edges,
+    ArcsList,
+    EdgesList,
+}
+
+// Splits a vertex line into its id and optional label.
+fn vertex(line: &str) -> Option<(&str, Option<&str>)> {
+    let line = line.trim();
+    let (id, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim_start();
+    let label = if let Some(quoted) = rest.strip_prefix('"') {
+        Some(&quoted[..quoted.find('"')?])
+    } else {
+        rest.split_whitespace().next()
+    };
+    Some((id, label))
+}
+
+/// Reads a Pajek network. A file with both arcs and edges is read as a
+/// directed graph, with every edge inserted in both directions.
+pub fn read(input: &str) -> Result<GraphList, String> {
+    // Vertex lines are optional, so only the labels that are given are
+    // stored until the graph is built.
+    let mut num_nodes = 0;
+    let mut labels: HashMap<usize, String> = HashMap::new();
+    let mut section = None;
+    // (from, to, weight, directed)
+    let mut links: Vec<(usize, usize, f64, bool)> = vec![];
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+        let error = |message: String| format!("Line {}: {message}", i + 1);
+        if let Some(keyword) = line.strip_prefix('*') {
+            let mut tokens = keyword.split_whitespace();
+            let name = tokens.next().unwrap_or("").to_lowercase();
+            if name == "network" {
+                continue;
+            }
+            section = Some(match name.as_str() {
+                "vertices" => {
+                    // Two-mode networks add a second count, which is ignored.
+                    let n = tokens
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .ok_or(error("expected `*Vertices n`".to_string()))?;
+                    num_nodes = n;
+                    labels.clear();
+                    Section::Vertices
+                }
+                "arcs" => Section::Arcs,
+                "edges" => Section::Edges,
+                "arcslist" => Section::ArcsList,
+                "edgeslist" => Section::EdgesList,
+                _ => return Err(error(format!("unsupported section `*{keyword}`"))),
+            });
+            continue;
+        }
+        let Some(section) = section else {
+            return Err(error("data before `*Vertices`".to_string()));
+        };
+        let node = |token: &str| -> Result<usize, String> {
+            match token.parse::<usize>() {
+                Ok(id) if id >= 1 && id <= num_nodes => Ok(id - 1),
+                _ => Err(error(format!("invalid vertex `{token}`"))),
+            }
+        };
+        match section {
+            Section::Vertices => {
+                let (id, label) = vertex(line).ok_or(error("unterminated label".to_string()))?;
+                let id = node(id)?;
+                match label {
+                    Some(label) => labels.insert(id, label.to_string()),
+                    None => labels.remove(&id),
+                };
+            }
+            Section::Arcs | Section::Edges => {
+                let tokens: Vec<&str> = line.split_whitespace().collect();
+                if tokens.len() < 2 {
+                    return Err(error("expected `from to [weight]`".to_string()));
+                }
+                let weight = match tokens.get(2) {
+                    Some(w) => w
+                        .parse()
+                        .map_err(|_| error(format!("invalid weight `{w}`")))?,
+                    None => 1.0,
+                };
+                let directed = section == Section::Arcs;
+                links.push((node(tokens[0])?, node(tokens[1])?, weight, directed));
+            }
+            Section::ArcsList | Section::EdgesList => {
+                let mut tokens = line.split_whitespace();
+                let from = node(tokens.next().unwrap())?;
+                for to in tokens {
+                    links.push((from, node(to)?, 1.0, section == Section::ArcsList));
+                }
+            }
+        }
+    }
+    let undirected = !links.iter().any(|&(_, _, _, directed)| directed);
+    let mut g = GraphList::try_with_nodes(undirected, num_nodes)?;
+    for (id, label) in labels {
+        g.nodes[id].label = Some(label);
+    }
+    for (from, to, weight, directed) in links {
+        g.insert_edge(from, to, weight)?;
+        if !directed && !undirected {
+            g.insert_edge(to, from, weight)?;
+        }
+    }
+    Ok(g)
+}
+
+/// Writes `g` with an `*Arcs` or `*Edges` section. Pajek labels cannot
+/// contain double quotes, so they are written as single quotes.
+pub fn write(g: &GraphList) -> String {
+    let mut out = String::new();
+    writeln!(out, "*Vertices {}", g.num_nodes()).unwrap();
+    for node in &g.nodes {
+        match node.label() {
+            Some(label) => {
+                writeln!(out, "{} \"{}\"", node.index() + 1, label.replace('"', "'")).unwrap()
+            }
+            None => writeln!(out, "{}", node.index() + 1).unwrap(),
+        }
+    }
+    out.push_str(if g.undirected { "*Edges\n" } else { "*Arcs\n" });
+    for node in &g.nodes {
+        for e in node.get_ordered_edge_list() {
+            if g.undirected && e.from > e.to {
+                continue;
+            }
+            writeln!(out, "{} {} {}", e.from + 1, e.to + 1, e.weight).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    fn weight(g: &GraphList, from: usize, to: usize) -> Option<OrderedFloat<f64>> {
+        g.get_edge(from, to).unwrap().map(|e| e.weight)
+    }
+
+    #[test]
+    fn test_read() {
+        let input = "% a small network
+*Network example
+*Vertices 4
+1 \"Node A\" 0.1 0.2 0.5
+2 B
+3
+*Edges
+1 2 2.5
+2 3
+";
+        let g = read(input).unwrap();
+        assert!(g.undirected);
+        assert_eq!(g.num_nodes(), 4);
+        assert_eq!(g.nodes[0].label(), Some("Node A"));
+        assert_eq!(g.nodes[1].label(), Some("B"));
+        assert_eq!(g.nodes[2].label(), None);
+        assert_eq!(weight(&g, 1, 0), Some(OrderedFloat(2.5)));
+        assert_eq!(weight(&g, 2, 1), Some(OrderedFloat(1.0)));
+    }
+
+    #[test]
+    fn test_read_mixed() {
+        let input = "*vertices 3
+*arcs
+1 2 3
+*edges
+2 3 4
+*arcslist
+3 1 2
+";
+        let g = read(input).unwrap();
+        assert!(!g.undirected);
+        assert_eq!(weight(&g, 0, 1), Some(OrderedFloat(3.0)));
+        assert_eq!(weight(&g, 1, 0), None);
+        assert_eq!(weight(&g, 1, 2), Some(OrderedFloat(4.0)));
+        assert_eq!(weight(&g, 2, 1), Some(OrderedFloat(1.0)));
+        assert_eq!(weight(&g, 2, 0), Some(OrderedFloat(1.0)));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("1 2\n").is_err());
+        assert!(read("*Vertices\n").is_err());
+        assert!(read("*Vertices 2\n*Arcs\n1 3\n").is_err());
+        assert!(read("*Vertices 2\n*Arcs\n0 1\n").is_err());
+        assert!(read("*Vertices 2\n*Arcs\n1 2 x\n").is_err());
+        assert!(read("*Vertices 2\n*Arcs\n1\n").is_err());
+        assert!(read("*Vertices 2\n1 \"open\n").is_err());
+        assert!(read("*Matrix\n").is_err());
+        assert_eq!(
+            read("*Vertices 18446744073709551615\n").unwrap_err(),
+            "Not enough memory for 18446744073709551615 nodes"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for undirected in [false, true] {
+            let mut graph = GraphList::with_nodes(undirected, 0);
+            graph.insert_node(Some("first node".to_string()));
+            graph.insert_node(None);
+            graph.insert_node(Some("C".to_string()));
+            graph.insert_edge(0, 1, 2.0).unwrap();
+            graph.insert_edge(2, 0, -0.5).unwrap();
+            graph.insert_edge(1, 1, 1.0).unwrap();
+            let read = read(&write(&graph)).unwrap();
+            assert_eq!(read.undirected, undirected);
+            for i in 0..3 {
+                assert_eq!(read.nodes[i].label(), graph.nodes[i].label());
+                for j in 0..3 {
+                    assert_eq!(read.get_edge(i, j).unwrap(), graph.get_edge(i, j).unwrap());
+                }
+            }
+        }
+    }
+}
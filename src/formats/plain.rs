@@ -0,0 +1,356 @@
+use crate::graph::{GraphList, GraphMatrix};
+use ordered_float::OrderedFloat;
+use std::fmt::Write;
+
+// The plain text inputs of programming contests. An edge list is a line
+// `n m` followed by `m` lines `u v [w]`:
+//
+// 3 2
+// 1 2 5
+// 2 3 1
+//
+// A matrix is a line `n` followed by `n` rows of `n` entries, where 0 means
+// no edge. Since nothing in the file says how to read it, the layout comes
+// from `PlainOptions`.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PlainFormat {
+    #[default]
+    EdgeList,
+    Matrix,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlainOptions {
+    pub format: PlainFormat,
+    /// Edge list node ids start at 1.
+    pub one_based: bool,
+    /// Edge lines have a weight column and matrix entries are weights.
+    /// Otherwise every edge weighs 1 and matrices hold 0s and 1s.
+    pub weighted: bool,
+    /// Only used when reading. Undirected edge lists list each edge once,
+    /// undirected matrices must be symmetric.
+    pub undirected: bool,
+}
+
+// Non-empty lines with their 1-based line numbers.
+fn lines(input: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split_whitespace().collect::<Vec<_>>()))
+        .filter(|(_, tokens)| !tokens.is_empty())
+}
+
+fn parse<T: std::str::FromStr>(token: &str, line: usize) -> Result<T, String> {
+    token
+        .parse()
+        .map_err(|_| format!("Line {line}: invalid number `{token}`"))
+}
+
+fn read_edge_list(input: &str, options: &PlainOptions) -> Result<GraphList, String> {
+    let mut lines = lines(input);
+    let (line, header) = lines.next().ok_or("Missing `n m` header")?;
+    if header.len() != 2 {
+        return Err(format!("Line {line}: expected `n m`"));
+    }
+    let n: usize = parse(header[0], line)?;
+    let m: usize = parse(header[1], line)?;
+    // Both counts come from the file, so nothing is allocated until the
+    // edges have been read.
+    let mut edges = vec![];
+    let columns = if options.weighted { 3 } else { 2 };
+    let first = usize::from(options.one_based);
+    for k in 0..m {
+        let (line, tokens) = lines
+            .next()
+            .ok_or(format!("Expected {m} edges, found {k}"))?;
+        if tokens.len() != columns {
+            return Err(format!("Line {line}: expected {columns} columns"));
+        }
+        let node = |token: &str| -> Result<usize, String> {
+            match parse::<usize>(token, line)?.checked_sub(first) {
+                Some(i) if i < n => Ok(i),
+                _ => Err(format!("Line {line}: node out of range: {token}")),
+            }
+        };
+        let weight = match tokens.get(2) {
+            Some(w) => parse(w, line)?,
+            None => 1.0,
+        };
+        edges.push((node(tokens[0])?, node(tokens[1])?, weight));
+    }
+    if let Some((line, _)) = lines.next() {
+        return Err(format!("Line {line}: more than {m} edges"));
+    }
+    let mut g = GraphList::try_with_nodes(options.undirected, n)?;
+    for (from, to, weight) in edges {
+        g.insert_edge(from, to, weight)?;
+    }
+    Ok(g)
+}
+
+fn read_matrix(input: &str, options: &PlainOptions) -> Result<GraphList, String> {
+    let mut lines = lines(input);
+    let (line, header) = lines.next().ok_or("Missing `n` header")?;
+    if header.len() != 1 {
+        return Err(format!("Line {line}: expected `n`"));
+    }
+    let n: usize = parse(header[0], line)?;
+    // Grown as rows are read, since `n` may be far beyond the input.
+    let mut rows: Vec<Vec<f64>> = vec![];
+    for i in 0..n {
+        let (line, tokens) = lines
+            .next()
+            .ok_or(format!("Expected {n} rows, found {i}"))?;
+        if tokens.len() != n {
+            return Err(format!("Line {line}: expected {n} entries"));
+        }
+        let row = tokens
+            .iter()
+            .map(|token| parse(token, line))
+            .collect::<Result<Vec<f64>, _>>()?;
+        if !options.weighted && row.iter().any(|&w| w != 0.0 && w != 1.0) {
+            return Err(format!("Line {line}: expected only 0 and 1"));
+        }
+        if options.undirected {
+            if let Some(j) = (0..i).find(|&j| rows[j][i] != row[j]) {
+                return Err(format!("Line {line}: entry ({i}, {j}) breaks symmetry"));
+            }
+        }
+        rows.push(row);
+    }
+    if let Some((line, _)) = lines.next() {
+        return Err(format!("Line {line}: more than {n} rows"));
+    }
+    let mut g = GraphList::with_nodes(options.undirected, n);
+    for (i, row) in rows.iter().enumerate() {
+        for (j, &weight) in row.iter().enumerate() {
+            if weight != 0.0 {
+                g.insert_edge(i, j, weight)?;
+            }
+        }
+    }
+    Ok(g)
+}
+
+pub fn read_graph_list(input: &str, options: &PlainOptions) -> Result<GraphList, String> {
+    match options.format {
+        PlainFormat::EdgeList => read_edge_list(input, options),
+        PlainFormat::Matrix => read_matrix(input, options),
+    }
+}
+
+pub fn read_graph_matrix<const N: usize>(
+    input: &str,
+    options: &PlainOptions,
+) -> Result<GraphMatrix<N>, String> {
+    GraphMatrix::from_graph_list(&read_graph_list(input, options)?)
+}
+
+/// Writes `g` in the layout of `options`. Undirected graphs write each edge
+/// once. Fails when unweighted output would lose weights other than 1, or
+/// when a matrix would lose an edge of weight 0.
+pub fn write_graph_list(g: &GraphList, options: &PlainOptions) -> Result<String, String> {
+    let edges: Vec<_> = g
+        .nodes
+        .iter()
+        .flat_map(|node| node.get_ordered_edge_list())
+        .filter(|e| !g.undirected || e.from <= e.to)
+        .collect();
+    if !options.weighted {
+        if let Some(e) = edges.iter().find(|e| e.weight != OrderedFloat(1.0)) {
+            return Err(format!(
+                "Edge {} -> {} has weight {}",
+                e.from, e.to, e.weight
+            ));
+        }
+    }
+    let n = g.num_nodes();
+    let mut out = String::new();
+    match options.format {
+        PlainFormat::EdgeList => {
+            let first = usize::from(options.one_based);
+            writeln!(out, "{n} {}", edges.len()).unwrap();
+            for e in edges {
+                write!(out, "{} {}", e.from + first, e.to + first).unwrap();
+                if options.weighted {
+                    write!(out, " {}", e.weight).unwrap();
+                }
+                out.push('\n');
+            }
+        }
+        PlainFormat::Matrix => {
+            writeln!(out, "{n}").unwrap();
+            for node in &g.nodes {
+                let mut row = vec![OrderedFloat(0.0); n];
+                for e in node.get_edge_list() {
+                    if e.weight == OrderedFloat(0.0) {
+                        return Err(format!("Edge {} -> {} has weight 0", e.from, e.to));
+                    }
+                    row[e.to] = e.weight;
+                }
+                let row: Vec<String> = row.iter().map(|w| w.to_string()).collect();
+                writeln!(out, "{}", row.join(" ")).unwrap();
+            }
+        }
+    }
+    Ok(out)
+}
+
+pub fn write_graph_matrix<const N: usize>(
+    m: &GraphMatrix<N>,
+    options: &PlainOptions,
+) -> Result<String, String> {
+    write_graph_list(&m.to_graph_list(), options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight(g: &GraphList, from: usize, to: usize) -> Option<f64> {
+        g.get_edge(from, to).unwrap().map(|e| e.weight.0)
+    }
+
+    #[test]
+    fn test_read_edge_list() {
+        let options = PlainOptions {
+            one_based: true,
+            weighted: true,
+            undirected: true,
+            ..Default::default()
+        };
+        let g = read_graph_list("3 2\n1 2 5\n\n2 3 1.5\n", &options).unwrap();
+        assert!(g.undirected);
+        assert_eq!(weight(&g, 1, 0), Some(5.0));
+        assert_eq!(weight(&g, 2, 1), Some(1.5));
+
+        let g = read_graph_list("2 1\n0 1\n", &PlainOptions::default()).unwrap();
+        assert!(!g.undirected);
+        assert_eq!(weight(&g, 0, 1), Some(1.0));
+        assert_eq!(weight(&g, 1, 0), None);
+    }
+
+    #[test]
+    fn test_read_edge_list_errors() {
+        let options = PlainOptions::default();
+        assert!(read_graph_list("", &options).is_err());
+        assert!(read_graph_list("2\n", &options).is_err());
+        assert!(read_graph_list("2 2\n0 1\n", &options).is_err());
+        assert!(read_graph_list("2 1\n0 1\n1 0\n", &options).is_err());
+        assert!(read_graph_list("2 1\n0 2\n", &options).is_err());
+        assert!(read_graph_list("2 1\n0 1 5\n", &options).is_err());
+        assert!(read_graph_list("2 1\n0 x\n", &options).is_err());
+        assert_eq!(
+            read_graph_list("18446744073709551615 0\n", &options).unwrap_err(),
+            "Not enough memory for 18446744073709551615 nodes"
+        );
+        assert_eq!(
+            read_graph_list("2 18446744073709551615\n0 1\n", &options).unwrap_err(),
+            "Expected 18446744073709551615 edges, found 1"
+        );
+        let one_based = PlainOptions {
+            one_based: true,
+            ..Default::default()
+        };
+        assert!(read_graph_list("2 1\n0 1\n", &one_based).is_err());
+    }
+
+    #[test]
+    fn test_read_matrix() {
+        let options = PlainOptions {
+            format: PlainFormat::Matrix,
+            weighted: true,
+            ..Default::default()
+        };
+        let g = read_graph_list("3\n0 2 0\n0 0 -1\n4 0 0\n", &options).unwrap();
+        assert_eq!(g.make_edge_list().len(), 3);
+        assert_eq!(weight(&g, 1, 2), Some(-1.0));
+        assert_eq!(weight(&g, 2, 1), None);
+
+        let undirected = PlainOptions {
+            weighted: false,
+            undirected: true,
+            ..options
+        };
+        let g = read_graph_list("2\n0 1\n1 1\n", &undirected).unwrap();
+        assert_eq!(weight(&g, 1, 0), Some(1.0));
+        assert_eq!(weight(&g, 1, 1), Some(1.0));
+        assert!(read_graph_list("2\n0 1\n0 0\n", &undirected).is_err());
+        assert!(read_graph_list("2\n0 2\n2 0\n", &undirected).is_err());
+        assert!(read_graph_list("2\n0 1\n", &undirected).is_err());
+        assert!(read_graph_list("2\n0 1 0\n1 0\n", &undirected).is_err());
+        assert!(read_graph_list("1\n0\n0\n", &undirected).is_err());
+        assert_eq!(
+            read_graph_list("18446744073709551615\n0 1\n", &undirected).unwrap_err(),
+            "Line 2: expected 18446744073709551615 entries"
+        );
+    }
+
+    #[test]
+    fn test_graph_matrix() {
+        let options = PlainOptions {
+            one_based: true,
+            weighted: true,
+            ..Default::default()
+        };
+        let m: GraphMatrix<3> = read_graph_matrix("3 1\n3 1 2.5\n", &options).unwrap();
+        assert_eq!(m.get_edge(2, 0), Some(OrderedFloat(2.5)));
+        assert!(read_graph_matrix::<2>("3 1\n3 1 2.5\n", &options).is_err());
+        assert_eq!(write_graph_matrix(&m, &options).unwrap(), "3 1\n3 1 2.5\n");
+    }
+
+    #[test]
+    fn test_write() {
+        let mut graph = GraphList::with_nodes(true, 3);
+        graph.insert_edge(0, 1, 2.0).unwrap();
+        graph.insert_edge(2, 1, 0.5).unwrap();
+        let edge_list = PlainOptions {
+            weighted: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            write_graph_list(&graph, &edge_list).unwrap(),
+            "3 2\n0 1 2\n1 2 0.5\n"
+        );
+        let matrix = PlainOptions {
+            format: PlainFormat::Matrix,
+            ..edge_list
+        };
+        assert_eq!(
+            write_graph_list(&graph, &matrix).unwrap(),
+            "3\n0 2 0\n2 0 0.5\n0 0.5 0\n"
+        );
+        // Unweighted output cannot keep these weights.
+        assert!(write_graph_list(&graph, &PlainOptions::default()).is_err());
+        graph.insert_edge(0, 2, 0.0).unwrap();
+        assert!(write_graph_list(&graph, &matrix).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for format in [PlainFormat::EdgeList, PlainFormat::Matrix] {
+            for undirected in [false, true] {
+                let mut graph = GraphList::with_nodes(undirected, 4);
+                graph.insert_edge(0, 1, 1.0).unwrap();
+                graph.insert_edge(3, 1, 1.0).unwrap();
+                graph.insert_edge(2, 2, 1.0).unwrap();
+                let options = PlainOptions {
+                    format,
+                    one_based: true,
+                    weighted: false,
+                    undirected,
+                };
+                let read = read_graph_list(&write_graph_list(&graph, &options).unwrap(), &options)
+                    .unwrap();
+                assert_eq!(read.undirected, undirected);
+                for i in 0..4 {
+                    for j in 0..4 {
+                        assert_eq!(read.get_edge(i, j).unwrap(), graph.get_edge(i, j).unwrap());
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use crate::graph::GraphList;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// SNAP edge lists (https://snap.stanford.edu/data/): `#` comment lines
+// followed by one `from to` pair per line, optionally with a weight as
+// third column. Node ids are arbitrary non-negative integers. A header
+// comment mentioning an "undirected graph" makes the graph undirected,
+// otherwise it is read as directed.
+
+/// A SNAP graph, with `ids[i]` holding the original id of node `i` and
+/// `indices` mapping original ids back to node indices.
+#[derive(Clone, Debug)]
+pub struct SnapGraph {
+    pub graph: GraphList,
+    pub ids: Vec<u64>,
+    pub indices: HashMap<u64, usize>,
+}
+
+impl SnapGraph {
+    pub fn index_of(&self, id: u64) -> Option<usize> {
+        self.indices.get(&id).copied()
+    }
+}
+
+/// Reads a SNAP edge list. Nodes are numbered in order of first appearance.
+pub fn read(input: &str) -> Result<SnapGraph, String> {
+    let undirected = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('#'))
+        .any(|line| line.to_lowercase().contains("undirected graph"));
+    let mut graph = GraphList::with_nodes(undirected, 0);
+    let mut ids = vec![];
+    let mut indices: HashMap<u64, usize> = HashMap::new();
+    let mut index_of = |id: u64, graph: &mut GraphList| {
+        *indices.entry(id).or_insert_with(|| {
+            ids.push(id);
+            graph.insert_node(None).index()
+        })
+    };
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() != 2 && tokens.len() != 3 {
+            return Err(format!("Line {}: expected `from to [weight]`", i + 1));
+        }
+        let parse_id = |token: &str| {
+            token
+                .parse::<u64>()
+                .map_err(|_| format!("Line {}: invalid node id: {token}", i + 1))
+        };
+        let from = parse_id(tokens[0])?;
+        let to = parse_id(tokens[1])?;
+        let weight = match tokens.get(2) {
+            Some(w) => w
+                .parse()
+                .map_err(|_| format!("Line {}: invalid weight: {w}", i + 1))?,
+            None => 1.0,
+        };
+        let from = index_of(from, &mut graph);
+        let to = index_of(to, &mut graph);
+        graph.insert_edge(from, to, weight)?;
+    }
+    Ok(SnapGraph {
+        graph,
+        ids,
+        indices,
+    })
+}
+
+/// Writes `g` as a SNAP edge list, using `ids` as the node ids when given
+/// and the node indices otherwise. Weights are only written when some edge
+/// has a weight other than 1. Isolated nodes cannot be represented.
+pub fn write(g: &GraphList, ids: Option<&[u64]>) -> Result<String, String> {
+    if let Some(ids) = ids {
+        if ids.len() != g.num_nodes() {
+            return Err(format!(
+                "Expected {} node ids, found {}",
+                g.num_nodes(),
+                ids.len()
+            ));
+        }
+    }
+    let id = |i: usize| ids.map_or(i as u64, |ids| ids[i]);
+    let edges: Vec<_> = g
+        .nodes
+        .iter()
+        .flat_map(|node| node.get_ordered_edge_list())
+        .filter(|e| !g.undirected || e.from <= e.to)
+        .collect();
+    let weighted = edges.iter().any(|e| e.weight.0 != 1.0);
+    let kind = if g.undirected {
+        "Undirected"
+    } else {
+        "Directed"
+    };
+    let mut out = format!("# {kind} graph\n");
+    writeln!(out, "# Nodes: {} Edges: {}", g.num_nodes(), edges.len()).unwrap();
+    if weighted {
+        writeln!(out, "# FromNodeId\tToNodeId\tWeight").unwrap();
+    } else {
+        writeln!(out, "# FromNodeId\tToNodeId").unwrap();
+    }
+    for e in edges {
+        write!(out, "{}\t{}", id(e.from), id(e.to)).unwrap();
+        if weighted {
+            write!(out, "\t{}", e.weight).unwrap();
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    #[test]
+    fn test_read_directed() {
+        let input = "# Directed graph (each unordered pair of nodes is saved once): Wiki-Vote.txt
+# Nodes: 3 Edges: 3
+# FromNodeId\tToNodeId
+30\t1412
+30\t3352
+3352\t30
+";
+        let snap = read(input).unwrap();
+        assert!(!snap.graph.undirected);
+        assert_eq!(snap.ids, vec![30, 1412, 3352]);
+        assert_eq!(snap.index_of(3352), Some(2));
+        assert_eq!(snap.index_of(7), None);
+        assert!(snap.graph.is_edge(0, 1));
+        assert!(snap.graph.is_edge(2, 0));
+        assert!(!snap.graph.is_edge(1, 0));
+    }
+
+    #[test]
+    fn test_read_undirected_weighted() {
+        let input = "# Undirected graph: ../../data/output/roadNet-CA.txt
+10 20 2.5
+
+20 30 1
+";
+        let snap = read(input).unwrap();
+        assert!(snap.graph.undirected);
+        assert_eq!(snap.graph.num_nodes(), 3);
+        assert_eq!(
+            snap.graph.get_edge(1, 0).unwrap().unwrap().weight,
+            OrderedFloat(2.5)
+        );
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("1\n").is_err());
+        assert!(read("1 -2\n").is_err());
+        assert!(read("1 2 x\n").is_err());
+        assert!(read("1 2 3 4\n").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = "# Undirected graph\n5 9\n9 100\n100 5\n";
+        let snap = read(input).unwrap();
+        let written = write(&snap.graph, Some(&snap.ids)).unwrap();
+        assert!(written.starts_with("# Undirected graph\n# Nodes: 3 Edges: 3\n"));
+        let read = read(&written).unwrap();
+        assert!(read.graph.undirected);
+        let mut ids = read.ids.clone();
+        ids.sort();
+        assert_eq!(ids, vec![5, 9, 100]);
+        for (a, b) in [(5, 9), (9, 100), (100, 5)] {
+            let (a, b) = (read.index_of(a).unwrap(), read.index_of(b).unwrap());
+            assert!(read.graph.is_edge(a, b));
+        }
+    }
+
+    #[test]
+    fn test_write_weighted_without_ids() {
+        let mut graph = GraphList::with_nodes(false, 3);
+        graph.insert_edge(0, 2, 1.5).unwrap();
+        graph.insert_edge(2, 1, 1.0).unwrap();
+        let written = write(&graph, None).unwrap();
+        assert!(written.ends_with("0\t2\t1.5\n2\t1\t1\n"));
+        assert!(write(&graph, Some(&[1, 2])).is_err());
+    }
+}
@@ -0,0 +1,418 @@
+use super::diagram::{component_color, Highlight, PATH_COLOR, PATH_FILL};
+use super::xml::escape;
+use crate::graph::GraphList;
+use crate::layout::{Layout, Point};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+// Standalone SVG drawings of a `GraphList` placed by one of the layouts in
+// `crate::layout`. The layout is scaled to fit the canvas. Every node is a
+// `<g class="node">` holding a circle and its label, every edge a
+// `<path class="edge">`; highlighted elements also get the `highlighted`
+// class so the output can be restyled with CSS.
+
+#[derive(Clone, Debug)]
+pub struct SvgStyle {
+    pub width: f64,
+    pub height: f64,
+    pub margin: f64,
+    pub node_radius: f64,
+    pub font_size: f64,
+    pub node_fill: String,
+    pub node_stroke: String,
+    pub edge_stroke: String,
+    pub highlight_stroke: String,
+    pub show_weights: bool,
+    /// Fill of individual nodes, overriding `node_fill`.
+    pub node_fills: HashMap<usize, String>,
+    pub highlighted_nodes: HashSet<usize>,
+    /// Edges as `(from, to)`. Either direction matches an undirected edge.
+    pub highlighted_edges: HashSet<(usize, usize)>,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        SvgStyle {
+            width: 800.0,
+            height: 600.0,
+            margin: 40.0,
+            node_radius: 16.0,
+            font_size: 12.0,
+            node_fill: "#ffffff".to_string(),
+            node_stroke: "#333333".to_string(),
+            edge_stroke: "#999999".to_string(),
+            highlight_stroke: PATH_COLOR.to_string(),
+            show_weights: true,
+            node_fills: HashMap::new(),
+            highlighted_nodes: HashSet::new(),
+            highlighted_edges: HashSet::new(),
+        }
+    }
+}
+
+impl SvgStyle {
+    /// Styles a path or a component assignment the same way the Mermaid and
+    /// PlantUML exports do.
+    pub fn highlight(&mut self, highlight: &Highlight) {
+        match highlight {
+            Highlight::Path(path) => {
+                for &node in path {
+                    self.highlighted_nodes.insert(node);
+                    self.node_fills.insert(node, PATH_FILL.to_string());
+                }
+                for step in path.windows(2) {
+                    self.highlighted_edges.insert((step[0], step[1]));
+                }
+            }
+            Highlight::Components(components) => {
+                for (node, &component) in components.iter().enumerate() {
+                    if let Some(color) = component_color(component) {
+                        self.node_fills.insert(node, color.to_string());
+                    }
+                }
+            }
+        }
+    }
+    fn is_highlighted_edge(&self, g: &GraphList, from: usize, to: usize) -> bool {
+        self.highlighted_edges.contains(&(from, to))
+            || (g.undirected && self.highlighted_edges.contains(&(to, from)))
+    }
+}
+
+// Maps layout coordinates onto the canvas, keeping the aspect ratio and
+// centering the drawing.
+struct Transform {
+    scale: f64,
+    dx: f64,
+    dy: f64,
+}
+
+impl Transform {
+    fn new(layout: &Layout, style: &SvgStyle) -> Self {
+        let points: Vec<&Point> = layout
+            .positions
+            .iter()
+            .chain(layout.edge_bends.values().flatten())
+            .collect();
+        if points.is_empty() {
+            return Transform {
+                scale: 1.0,
+                dx: 0.0,
+                dy: 0.0,
+            };
+        }
+        let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let max_x = points.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+        let min_y = points.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+        let max_y = points.iter().map(|p| p.y).fold(f64::NEG_INFINITY, f64::max);
+        let available_x = style.width - 2.0 * style.margin;
+        let available_y = style.height - 2.0 * style.margin;
+        let scale_x = if max_x > min_x {
+            available_x / (max_x - min_x)
+        } else {
+            f64::INFINITY
+        };
+        let scale_y = if max_y > min_y {
+            available_y / (max_y - min_y)
+        } else {
+            f64::INFINITY
+        };
+        let scale = match scale_x.min(scale_y) {
+            s if s.is_finite() => s,
+            _ => 1.0,
+        };
+        Transform {
+            scale,
+            dx: style.margin + (available_x - (max_x - min_x) * scale) / 2.0 - min_x * scale,
+            dy: style.margin + (available_y - (max_y - min_y) * scale) / 2.0 - min_y * scale,
+        }
+    }
+    fn apply(&self, p: Point) -> Point {
+        Point::new(p.x * self.scale + self.dx, p.y * self.scale + self.dy)
+    }
+}
+
+// Moves `from` by `distance` towards `to`.
+fn towards(from: Point, to: Point, distance: f64) -> Point {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length <= distance {
+        return from;
+    }
+    Point::new(
+        from.x + dx / length * distance,
+        from.y + dy / length * distance,
+    )
+}
+
+fn marker(id: &str, color: &str) -> String {
+    format!(
+        "    <marker id=\"{id}\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto-start-reverse\">\n      <path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"{color}\"/>\n    </marker>\n"
+    )
+}
+
+/// Draws `g` with the node positions of `layout`, which must have been
+/// computed for `g`.
+pub fn write(g: &GraphList, layout: &Layout, style: &SvgStyle) -> Result<String, String> {
+    if layout.positions.len() != g.num_nodes() {
+        return Err(format!(
+            "Layout has {} positions for {} nodes",
+            layout.positions.len(),
+            g.num_nodes()
+        ));
+    }
+    let transform = Transform::new(layout, style);
+    let position = |i: usize| transform.apply(layout.positions[i]);
+    let r = style.node_radius;
+    let mut out = String::new();
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" font-family=\"sans-serif\" font-size=\"{2}\">",
+        style.width, style.height, style.font_size
+    )
+    .unwrap();
+    if !g.undirected {
+        out.push_str("  <defs>\n");
+        out.push_str(&marker("arrow", &style.edge_stroke));
+        out.push_str(&marker("arrow-highlighted", &style.highlight_stroke));
+        out.push_str("  </defs>\n");
+    }
+
+    out.push_str("  <g class=\"edges\" fill=\"none\">\n");
+    for node in &g.nodes {
+        for e in node.get_ordered_edge_list() {
+            if g.undirected && e.from > e.to {
+                continue;
+            }
+            let highlighted = style.is_highlighted_edge(g, e.from, e.to);
+            let (class, stroke, width, arrow) = if highlighted {
+                (
+                    "edge highlighted",
+                    &style.highlight_stroke,
+                    3,
+                    "arrow-highlighted",
+                )
+            } else {
+                ("edge", &style.edge_stroke, 1, "arrow")
+            };
+            let (d, label_at) = if e.from == e.to {
+                let p = position(e.from);
+                let d = format!(
+                    "M {:.2} {:.2} C {:.2} {:.2} {:.2} {:.2} {:.2} {:.2}",
+                    p.x - r / 2.0,
+                    p.y - r * 0.85,
+                    p.x - 2.0 * r,
+                    p.y - 3.5 * r,
+                    p.x + 2.0 * r,
+                    p.y - 3.5 * r,
+                    p.x + r / 2.0,
+                    p.y - r * 0.85
+                );
+                (d, Point::new(p.x, p.y - 3.0 * r))
+            } else {
+                let mut points = vec![position(e.from)];
+                let bends = layout.edge_bends.get(&(e.from, e.to));
+                points.extend(bends.into_iter().flatten().map(|&b| transform.apply(b)));
+                points.push(position(e.to));
+                let last = points.len() - 1;
+                points[0] = towards(points[0], points[1], r);
+                points[last] = towards(points[last], points[last - 1], r);
+                let middle = (last - 1) / 2;
+                let label_at = Point::new(
+                    (points[middle].x + points[middle + 1].x) / 2.0,
+                    (points[middle].y + points[middle + 1].y) / 2.0,
+                );
+                let d: Vec<String> = points
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| {
+                        let command = if i == 0 { "M" } else { "L" };
+                        format!("{command} {:.2} {:.2}", p.x, p.y)
+                    })
+                    .collect();
+                (d.join(" "), label_at)
+            };
+            write!(
+                out,
+                "    <path class=\"{class}\" d=\"{d}\" stroke=\"{stroke}\" stroke-width=\"{width}\""
+            )
+            .unwrap();
+            if !g.undirected {
+                write!(out, " marker-end=\"url(#{arrow})\"").unwrap();
+            }
+            out.push_str("/>\n");
+            if style.show_weights {
+                writeln!(
+                    out,
+                    "    <text class=\"weight\" x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"middle\" fill=\"{stroke}\" stroke=\"none\">{}</text>",
+                    label_at.x, label_at.y, e.weight
+                )
+                .unwrap();
+            }
+        }
+    }
+    out.push_str("  </g>\n");
+
+    out.push_str("  <g class=\"nodes\">\n");
+    for node in &g.nodes {
+        let i = node.index();
+        let p = position(i);
+        let highlighted = style.highlighted_nodes.contains(&i);
+        let class = if highlighted {
+            "node highlighted"
+        } else {
+            "node"
+        };
+        let fill = style.node_fills.get(&i).unwrap_or(&style.node_fill);
+        let (stroke, width) = if highlighted {
+            (&style.highlight_stroke, 3)
+        } else {
+            (&style.node_stroke, 1)
+        };
+        let text = node.label().map_or_else(|| i.to_string(), escape);
+        writeln!(out, "    <g class=\"{class}\" id=\"n{i}\">").unwrap();
+        writeln!(
+            out,
+            "      <circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{r}\" fill=\"{fill}\" stroke=\"{stroke}\" stroke-width=\"{width}\"/>",
+            p.x, p.y
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "      <text x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"middle\" dominant-baseline=\"central\">{text}</text>",
+            p.x, p.y
+        )
+        .unwrap();
+        out.push_str("    </g>\n");
+    }
+    out.push_str("  </g>\n</svg>\n");
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::xml;
+    use crate::layout::{circular_layout, layered_layout};
+
+    fn create_test_graph() -> GraphList {
+        let mut graph = GraphList::with_nodes(false, 0);
+        graph.insert_node(Some("a < b".to_string()));
+        graph.insert_node(None);
+        graph.insert_node(None);
+        graph.insert_edge(0, 1, 2.5).unwrap();
+        graph.insert_edge(1, 2, 1.0).unwrap();
+        graph.insert_edge(0, 2, 4.0).unwrap();
+        graph.insert_edge(2, 2, 1.0).unwrap();
+        graph
+    }
+
+    fn circles(svg: &xml::Element) -> Vec<(f64, f64)> {
+        let nodes = svg
+            .children
+            .iter()
+            .find(|c| c.attribute("class") == Some("nodes"))
+            .unwrap();
+        nodes
+            .children
+            .iter()
+            .map(|node| {
+                let circle = node.child("circle").unwrap();
+                let coordinate = |name| circle.attribute(name).unwrap().parse().unwrap();
+                (coordinate("cx"), coordinate("cy"))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_write_is_well_formed() {
+        let graph = create_test_graph();
+        let out = write(&graph, &circular_layout(&graph), &SvgStyle::default()).unwrap();
+        let svg = xml::parse(&out).unwrap();
+        assert_eq!(svg.name, "svg");
+        assert_eq!(svg.attribute("width"), Some("800"));
+        assert!(svg.child("defs").is_some());
+        assert!(out.contains(">a &lt; b</text>"));
+        assert!(out.contains(">2.5</text>"));
+        assert_eq!(out.matches("class=\"edge\"").count(), 4);
+        assert_eq!(out.matches("marker-end=\"url(#arrow)\"").count(), 4);
+        assert!(!out.contains(" highlighted\""));
+    }
+
+    #[test]
+    fn test_layout_fits_canvas() {
+        let graph = create_test_graph();
+        let style = SvgStyle::default();
+        let svg = xml::parse(&write(&graph, &circular_layout(&graph), &style).unwrap()).unwrap();
+        for (x, y) in circles(&svg) {
+            assert!(x >= style.margin && x <= style.width - style.margin);
+            assert!(y >= style.margin && y <= style.height - style.margin);
+        }
+        // A single node is centered.
+        let single = GraphList::with_nodes(true, 1);
+        let svg = xml::parse(&write(&single, &circular_layout(&single), &style).unwrap()).unwrap();
+        assert_eq!(circles(&svg), vec![(400.0, 300.0)]);
+    }
+
+    #[test]
+    fn test_highlight_path() {
+        let mut graph = GraphList::with_nodes(true, 3);
+        graph.insert_edge(0, 1, 2.5).unwrap();
+        graph.insert_edge(1, 2, 1.0).unwrap();
+        graph.insert_edge(0, 2, 4.0).unwrap();
+        let mut style = SvgStyle {
+            show_weights: false,
+            ..Default::default()
+        };
+        style.highlight(&Highlight::Path(vec![2, 1]));
+        let out = write(&graph, &circular_layout(&graph), &style).unwrap();
+        assert_eq!(out.matches("class=\"edge highlighted\"").count(), 1);
+        assert_eq!(out.matches("class=\"node highlighted\"").count(), 2);
+        assert!(!out.contains("marker"));
+        assert!(!out.contains("class=\"weight\""));
+    }
+
+    fn create_dag() -> GraphList {
+        let mut graph = GraphList::with_nodes(false, 3);
+        graph.insert_edge(0, 1, 1.0).unwrap();
+        graph.insert_edge(1, 2, 1.0).unwrap();
+        graph.insert_edge(0, 2, 1.0).unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_layered_edges_follow_bends() {
+        let graph = create_dag();
+        let out = write(
+            &graph,
+            &layered_layout(&graph).unwrap(),
+            &SvgStyle::default(),
+        )
+        .unwrap();
+        // 0 -> 2 skips a layer, so its path has a bend: M, L, L.
+        assert!(out
+            .lines()
+            .any(|line| line.contains("class=\"edge\"") && line.matches(" L ").count() == 2));
+    }
+
+    #[test]
+    fn test_layout_of_another_graph() {
+        let graph = create_test_graph();
+        let layout = circular_layout(&GraphList::with_nodes(false, 2));
+        assert_eq!(
+            write(&graph, &layout, &SvgStyle::default()).unwrap_err(),
+            "Layout has 2 positions for 3 nodes"
+        );
+    }
+
+    #[test]
+    fn test_highlight_components() {
+        let graph = create_test_graph();
+        let mut style = SvgStyle::default();
+        style.highlight(&Highlight::Components(vec![0, 1, -1]));
+        let out = write(&graph, &circular_layout(&graph), &style).unwrap();
+        assert!(out.contains("fill=\"#8dd3c7\""));
+        assert!(out.contains("fill=\"#ffffb3\""));
+        assert_eq!(out.matches("fill=\"#ffffff\"").count(), 1);
+    }
+}
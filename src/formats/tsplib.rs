@@ -0,0 +1,578 @@
+use crate::graph::{GraphList, GraphMatrix};
+use crate::layout::{Layout, Point};
+use std::collections::HashMap;
+
+// TSPLIB instances, as described in Reinelt's "TSPLIB 95". A file is a list
+// of `KEY : value` specification lines followed by data sections:
+//
+// NAME : example
+// TYPE : TSP
+// DIMENSION : 3
+// EDGE_WEIGHT_TYPE : EUC_2D
+// NODE_COORD_SECTION
+// 1 0 0
+// 2 3 4
+// 3 6 8
+// EOF
+//
+// Nodes are 1-based in the file and 0-based everywhere else. Distances
+// follow the TSPLIB definitions, so they are integral except for explicit
+// matrices holding reals.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EdgeWeightType {
+    Euc2d,
+    Ceil2d,
+    Man2d,
+    Max2d,
+    Geo,
+    Att,
+    Explicit,
+}
+
+impl EdgeWeightType {
+    fn parse(value: &str) -> Result<Self, String> {
+        Ok(match value {
+            "EUC_2D" => EdgeWeightType::Euc2d,
+            "CEIL_2D" => EdgeWeightType::Ceil2d,
+            "MAN_2D" => EdgeWeightType::Man2d,
+            "MAX_2D" => EdgeWeightType::Max2d,
+            "GEO" => EdgeWeightType::Geo,
+            "ATT" => EdgeWeightType::Att,
+            "EXPLICIT" => EdgeWeightType::Explicit,
+            _ => return Err(format!("Unsupported EDGE_WEIGHT_TYPE: {value}")),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TsplibInstance {
+    pub name: Option<String>,
+    pub comment: Option<String>,
+    /// `TSP` instances are symmetric, `ATSP` ones are not.
+    pub symmetric: bool,
+    pub dimension: usize,
+    pub edge_weight_type: EdgeWeightType,
+    /// Coordinates from `NODE_COORD_SECTION`, or from `DISPLAY_DATA_SECTION`
+    /// for explicit instances that have one.
+    pub coordinates: Option<Vec<Point>>,
+    weights: Option<Vec<Vec<f64>>>,
+}
+
+fn nint(x: f64) -> f64 {
+    (x + 0.5).floor()
+}
+
+// Latitude or longitude in radians from TSPLIB's `DDD.MM` notation.
+fn geo_radians(x: f64) -> f64 {
+    // TSPLIB's reference distances are computed with this truncated value.
+    #[allow(clippy::approx_constant)]
+    const PI: f64 = 3.141592;
+    let degrees = x.trunc();
+    let minutes = x - degrees;
+    PI * (degrees + 5.0 * minutes / 3.0) / 180.0
+}
+
+impl TsplibInstance {
+    /// Distance from node `i` to node `j`.
+    pub fn distance(&self, i: usize, j: usize) -> f64 {
+        if let Some(weights) = &self.weights {
+            return weights[i][j];
+        }
+        let p = self.coordinates.as_ref().expect("coordinates are read")[i];
+        let q = self.coordinates.as_ref().expect("coordinates are read")[j];
+        let (dx, dy) = (p.x - q.x, p.y - q.y);
+        match self.edge_weight_type {
+            EdgeWeightType::Euc2d => nint((dx * dx + dy * dy).sqrt()),
+            EdgeWeightType::Ceil2d => (dx * dx + dy * dy).sqrt().ceil(),
+            EdgeWeightType::Man2d => nint(dx.abs() + dy.abs()),
+            EdgeWeightType::Max2d => nint(dx.abs()).max(nint(dy.abs())),
+            EdgeWeightType::Att => {
+                let r = ((dx * dx + dy * dy) / 10.0).sqrt();
+                let t = nint(r);
+                if t < r {
+                    t + 1.0
+                } else {
+                    t
+                }
+            }
+            EdgeWeightType::Geo => {
+                const RRR: f64 = 6378.388;
+                let (lat_p, long_p) = (geo_radians(p.x), geo_radians(p.y));
+                let (lat_q, long_q) = (geo_radians(q.x), geo_radians(q.y));
+                let q1 = (long_p - long_q).cos();
+                let q2 = (lat_p - lat_q).cos();
+                let q3 = (lat_p + lat_q).cos();
+                (RRR * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0).trunc()
+            }
+            EdgeWeightType::Explicit => unreachable!("explicit instances have weights"),
+        }
+    }
+
+    /// The complete graph of the instance, without self-loops. Symmetric
+    /// instances give an undirected graph.
+    pub fn to_graph_list(&self) -> GraphList {
+        let n = self.dimension;
+        let mut g = GraphList::with_nodes(self.symmetric, n);
+        for i in 0..n {
+            g.nodes[i].reserve_edges(n - 1);
+            let first = if self.symmetric { i + 1 } else { 0 };
+            for j in first..n {
+                if i != j {
+                    g.insert_edge(i, j, self.distance(i, j)).unwrap();
+                }
+            }
+        }
+        g
+    }
+
+    /// The instance as a dense matrix. `GraphMatrix` has no room for
+    /// zero-weight edges, so distinct nodes at distance 0 are not adjacent.
+    pub fn to_graph_matrix<const N: usize>(&self) -> Result<GraphMatrix<N>, String> {
+        if self.dimension != N {
+            return Err(format!(
+                "Instance has {} nodes, expected {N}",
+                self.dimension
+            ));
+        }
+        let mut m = GraphMatrix::new(self.symmetric);
+        for i in 0..N {
+            for j in 0..N {
+                if i != j {
+                    m.set_edge(i, j, self.distance(i, j).into())?;
+                }
+            }
+        }
+        Ok(m)
+    }
+
+    /// The node coordinates as a layout, ready to be drawn.
+    pub fn layout(&self) -> Option<Layout> {
+        Some(Layout {
+            positions: self.coordinates.clone()?,
+            edge_bends: HashMap::new(),
+        })
+    }
+
+    /// Length of the closed tour visiting `tour` in order. Fails unless the
+    /// tour visits every node exactly once.
+    pub fn tour_length(&self, tour: &[usize]) -> Result<f64, String> {
+        let mut seen = vec![false; self.dimension];
+        for &node in tour {
+            match seen.get_mut(node) {
+                None => return Err(format!("Tour node out of range: {}", node + 1)),
+                Some(true) => return Err(format!("Tour visits node {} twice", node + 1)),
+                Some(visited) => *visited = true,
+            }
+        }
+        if tour.len() != self.dimension {
+            return Err(format!(
+                "Tour has {} nodes, expected {}",
+                tour.len(),
+                self.dimension
+            ));
+        }
+        Ok((0..tour.len())
+            .map(|i| self.distance(tour[i], tour[(i + 1) % tour.len()]))
+            .sum())
+    }
+}
+
+struct Lines<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    line: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(input: &'a str) -> Self {
+        Lines {
+            lines: input.lines().enumerate(),
+            line: 0,
+        }
+    }
+    fn next(&mut self) -> Option<&'a str> {
+        let (i, line) = self.lines.next()?;
+        self.line = i + 1;
+        Some(line.trim())
+    }
+    // Reads `count` numbers, spread over as many lines as needed.
+    fn numbers(&mut self, count: usize, section: &str) -> Result<Vec<f64>, String> {
+        // `count` comes from the file, so the numbers are not preallocated.
+        let mut numbers = vec![];
+        while numbers.len() < count {
+            let line = self
+                .next()
+                .ok_or(format!("{section} ends after {} numbers", numbers.len()))?;
+            for token in line.split_whitespace() {
+                let number = token
+                    .parse()
+                    .map_err(|_| format!("Line {}: invalid number `{token}`", self.line))?;
+                numbers.push(number);
+            }
+        }
+        if numbers.len() > count {
+            return Err(format!("Line {}: too many numbers in {section}", self.line));
+        }
+        Ok(numbers)
+    }
+    // Reads 1-based node ids up to the terminating `-1`.
+    fn nodes(&mut self, dimension: Option<usize>) -> Result<Vec<usize>, String> {
+        let mut nodes = vec![];
+        while let Some(line) = self.next() {
+            for token in line.split_whitespace() {
+                let id: i64 = token
+                    .parse()
+                    .map_err(|_| format!("Line {}: invalid node `{token}`", self.line))?;
+                if id == -1 {
+                    return Ok(nodes);
+                }
+                if id < 1 || dimension.is_some_and(|n| id as usize > n) {
+                    return Err(format!("Line {}: node out of range: {id}", self.line));
+                }
+                nodes.push(id as usize - 1);
+            }
+        }
+        // `-1` is optional when the section is the last thing in the file.
+        Ok(nodes)
+    }
+}
+
+// Places the weights of an `EDGE_WEIGHT_SECTION` in a full matrix.
+fn weight_matrix(format: &str, n: usize, lines: &mut Lines) -> Result<Vec<Vec<f64>>, String> {
+    // Column-wise triangles of a symmetric matrix list the same numbers as
+    // the opposite row-wise triangle.
+    let format = match format {
+        "UPPER_COL" => "LOWER_ROW",
+        "LOWER_COL" => "UPPER_ROW",
+        "UPPER_DIAG_COL" => "LOWER_DIAG_ROW",
+        "LOWER_DIAG_COL" => "UPPER_DIAG_ROW",
+        format => format,
+    };
+    let triangle = |side: usize| side.checked_mul(side + 1).map(|cells| cells / 2);
+    let (count, cells): (_, Box<dyn Iterator<Item = (usize, usize)>>) = match format {
+        "FULL_MATRIX" => (
+            n.checked_mul(n),
+            Box::new((0..n).flat_map(move |i| (0..n).map(move |j| (i, j)))),
+        ),
+        "UPPER_ROW" => (
+            triangle(n.saturating_sub(1)),
+            Box::new((0..n).flat_map(move |i| (i + 1..n).map(move |j| (i, j)))),
+        ),
+        "LOWER_ROW" => (
+            triangle(n.saturating_sub(1)),
+            Box::new((0..n).flat_map(|i| (0..i).map(move |j| (i, j)))),
+        ),
+        "UPPER_DIAG_ROW" => (
+            triangle(n),
+            Box::new((0..n).flat_map(move |i| (i..n).map(move |j| (i, j)))),
+        ),
+        "LOWER_DIAG_ROW" => (
+            triangle(n),
+            Box::new((0..n).flat_map(|i| (0..=i).map(move |j| (i, j)))),
+        ),
+        _ => return Err(format!("Unsupported EDGE_WEIGHT_FORMAT: {format}")),
+    };
+    let count = count.ok_or(format!("EDGE_WEIGHT_SECTION: DIMENSION {n} is too large"))?;
+    let numbers = lines.numbers(count, "EDGE_WEIGHT_SECTION")?;
+    let full = format == "FULL_MATRIX";
+    let mut weights = vec![vec![0.0; n]; n];
+    for ((i, j), weight) in cells.zip(numbers) {
+        weights[i][j] = weight;
+        if !full {
+            weights[j][i] = weight;
+        }
+    }
+    Ok(weights)
+}
+
+fn coordinates(n: usize, lines: &mut Lines, section: &str) -> Result<Vec<Point>, String> {
+    let count = n
+        .checked_mul(3)
+        .ok_or(format!("{section}: DIMENSION {n} is too large"))?;
+    let numbers = lines.numbers(count, section)?;
+    let mut points = vec![None; n];
+    for entry in numbers.chunks(3) {
+        let id = entry[0];
+        if id.fract() != 0.0 || id < 1.0 || id > n as f64 {
+            return Err(format!("{section}: node out of range: {id}"));
+        }
+        if points[id as usize - 1]
+            .replace(Point::new(entry[1], entry[2]))
+            .is_some()
+        {
+            return Err(format!("{section}: duplicate node {id}"));
+        }
+    }
+    // With 3n numbers and no duplicates, every node has its coordinates.
+    Ok(points.into_iter().map(Option::unwrap).collect())
+}
+
+pub fn read(input: &str) -> Result<TsplibInstance, String> {
+    let mut lines = Lines::new(input);
+    let mut spec: HashMap<String, String> = HashMap::new();
+    let mut coords = None;
+    let mut display = None;
+    let mut weights = None;
+    while let Some(line) = lines.next() {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            spec.insert(key.trim().to_string(), value.trim().to_string());
+            continue;
+        }
+        let dimension = || -> Result<usize, String> {
+            spec.get("DIMENSION")
+                .ok_or(format!("{line} before DIMENSION"))?
+                .parse()
+                .map_err(|_| "Invalid DIMENSION".to_string())
+        };
+        match line {
+            "EOF" => break,
+            "NODE_COORD_SECTION" => {
+                if spec
+                    .get("NODE_COORD_TYPE")
+                    .is_some_and(|t| t != "TWOD_COORDS")
+                {
+                    return Err("Only TWOD_COORDS are supported".to_string());
+                }
+                coords = Some(coordinates(dimension()?, &mut lines, line)?);
+            }
+            "DISPLAY_DATA_SECTION" => {
+                display = Some(coordinates(dimension()?, &mut lines, line)?);
+            }
+            "EDGE_WEIGHT_SECTION" => {
+                let n = dimension()?;
+                let format = spec
+                    .get("EDGE_WEIGHT_FORMAT")
+                    .ok_or("EDGE_WEIGHT_SECTION without EDGE_WEIGHT_FORMAT")?;
+                weights = Some(weight_matrix(format, n, &mut lines)?);
+            }
+            "FIXED_EDGES_SECTION" => {
+                lines.nodes(None)?;
+            }
+            _ => return Err(format!("Line {}: unexpected `{line}`", lines.line)),
+        }
+    }
+
+    let symmetric = match spec.get("TYPE").map(String::as_str) {
+        Some("TSP") => true,
+        Some("ATSP") => false,
+        Some(other) => return Err(format!("Unsupported TYPE: {other}")),
+        None => return Err("Missing TYPE".to_string()),
+    };
+    let dimension = spec
+        .get("DIMENSION")
+        .ok_or("Missing DIMENSION")?
+        .parse()
+        .map_err(|_| "Invalid DIMENSION".to_string())?;
+    let edge_weight_type = EdgeWeightType::parse(
+        spec.get("EDGE_WEIGHT_TYPE")
+            .ok_or("Missing EDGE_WEIGHT_TYPE")?,
+    )?;
+    if edge_weight_type == EdgeWeightType::Explicit {
+        if weights.is_none() {
+            return Err("Missing EDGE_WEIGHT_SECTION".to_string());
+        }
+        if !symmetric && spec.get("EDGE_WEIGHT_FORMAT").map(String::as_str) != Some("FULL_MATRIX") {
+            return Err("ATSP instances need a FULL_MATRIX".to_string());
+        }
+    } else {
+        if coords.is_none() {
+            return Err("Missing NODE_COORD_SECTION".to_string());
+        }
+        weights = None;
+    }
+    Ok(TsplibInstance {
+        name: spec.remove("NAME"),
+        comment: spec.remove("COMMENT"),
+        symmetric,
+        dimension,
+        edge_weight_type,
+        coordinates: coords.or(display),
+        weights,
+    })
+}
+
+/// Reads a `.opt.tour` (or any `TYPE : TOUR`) file into 0-based nodes.
+pub fn read_tour(input: &str) -> Result<Vec<usize>, String> {
+    let mut lines = Lines::new(input);
+    let mut dimension = None;
+    while let Some(line) = lines.next() {
+        if let Some((key, value)) = line.split_once(':') {
+            if key.trim() == "DIMENSION" {
+                let n = value.trim().parse();
+                dimension = Some(n.map_err(|_| "Invalid DIMENSION".to_string())?);
+            }
+            continue;
+        }
+        if line == "TOUR_SECTION" {
+            let tour = lines.nodes(dimension)?;
+            if let Some(n) = dimension.filter(|&n| n != tour.len()) {
+                return Err(format!("Tour has {} nodes, expected {n}", tour.len()));
+            }
+            return Ok(tour);
+        }
+    }
+    Err("Missing TOUR_SECTION".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    const EUC: &str = "NAME : square
+COMMENT : four corners of a 3x4 rectangle
+TYPE : TSP
+DIMENSION : 4
+EDGE_WEIGHT_TYPE : EUC_2D
+NODE_COORD_SECTION
+1 0 0
+2 3 0
+3 3 4
+4 0 4.4
+EOF
+";
+
+    #[test]
+    fn test_read_euc_2d() {
+        let instance = read(EUC).unwrap();
+        assert_eq!(instance.name.as_deref(), Some("square"));
+        assert!(instance.symmetric);
+        assert_eq!(instance.edge_weight_type, EdgeWeightType::Euc2d);
+        assert_eq!(instance.distance(0, 1), 3.0);
+        assert_eq!(instance.distance(0, 2), 5.0);
+        // 4.4 rounds to the nearest integer.
+        assert_eq!(instance.distance(0, 3), 4.0);
+        assert_eq!(
+            instance.layout().unwrap().positions[3],
+            Point::new(0.0, 4.4)
+        );
+
+        let g = instance.to_graph_list();
+        assert!(g.undirected);
+        assert_eq!(g.make_edge_list().len(), 12);
+        assert_eq!(g.get_edge(2, 0).unwrap().unwrap().weight, OrderedFloat(5.0));
+        assert!(!g.is_edge(1, 1));
+
+        let m = instance.to_graph_matrix::<4>().unwrap();
+        assert_eq!(m.get_edge(1, 2), Some(OrderedFloat(4.0)));
+        assert_eq!(m.get_edge(1, 1), Some(OrderedFloat(0.0)));
+        assert!(instance.to_graph_matrix::<3>().is_err());
+    }
+
+    #[test]
+    fn test_att_and_geo() {
+        let att = read(&EUC.replace("EUC_2D", "ATT")).unwrap();
+        assert_eq!(att.distance(0, 1), 1.0);
+        // sqrt(19.36 / 10) = 1.39 rounds down to 1, and is bumped up.
+        assert_eq!(att.distance(0, 3), 2.0);
+
+        // The first two cities of ulysses16.
+        let geo = read(
+            "TYPE: TSP\nDIMENSION: 2\nEDGE_WEIGHT_TYPE: GEO\nNODE_COORD_SECTION\n1 38.24 20.42\n2 39.57 26.15\n",
+        )
+        .unwrap();
+        assert_eq!(geo.distance(0, 1), 509.0);
+        assert_eq!(geo.distance(1, 0), 509.0);
+    }
+
+    #[test]
+    fn test_read_explicit() {
+        let upper = "TYPE : TSP
+DIMENSION : 3
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : UPPER_ROW
+EDGE_WEIGHT_SECTION
+ 1 2
+ 3
+EOF";
+        let instance = read(upper).unwrap();
+        assert_eq!(instance.distance(0, 2), 2.0);
+        assert_eq!(instance.distance(2, 1), 3.0);
+        assert!(instance.coordinates.is_none());
+        let lower = upper
+            .replace("UPPER_ROW", "LOWER_DIAG_ROW")
+            .replace(" 1 2\n 3", "0\n1 0\n2 3 0");
+        let instance = read(&lower).unwrap();
+        assert_eq!(instance.distance(0, 2), 2.0);
+        assert_eq!(instance.distance(2, 1), 3.0);
+        assert!(read(&upper.replace("UPPER_ROW", "FUNCTION")).is_err());
+        assert!(read(&upper.replace(" 3\n", " 3 4\n")).is_err());
+
+        let full = "TYPE : ATSP
+DIMENSION : 2
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : FULL_MATRIX
+EDGE_WEIGHT_SECTION
+9999 5
+7 9999
+EOF";
+        let instance = read(full).unwrap();
+        assert!(!instance.symmetric);
+        let g = instance.to_graph_list();
+        assert!(!g.undirected);
+        assert_eq!(g.get_edge(0, 1).unwrap().unwrap().weight, OrderedFloat(5.0));
+        assert_eq!(g.get_edge(1, 0).unwrap().unwrap().weight, OrderedFloat(7.0));
+        assert_eq!(instance.tour_length(&[1, 0]).unwrap(), 12.0);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("TYPE : TSP\nNODE_COORD_SECTION\n").is_err());
+        assert!(read(&EUC.replace("EUC_2D", "EUC_3D")).is_err());
+        assert!(read(&EUC.replace("TSP", "CVRP")).is_err());
+        assert!(read(&EUC.replace("4 0 4.4\n", "")).is_err());
+        assert!(read(&EUC.replace("4 0 4.4", "3 0 4.4")).is_err());
+        assert!(read(&EUC.replace("4 0 4.4", "5 0 4.4")).is_err());
+        assert!(read(&EUC.replace("1 0 0", "1 0 x")).is_err());
+        assert!(read(&EUC.replace("NODE_COORD_SECTION", "")).is_err());
+        // Dimensions beyond the numbers in the file
+        assert_eq!(
+            read(&EUC.replace("DIMENSION : 4", "DIMENSION : 18446744073709551615")).unwrap_err(),
+            "NODE_COORD_SECTION: DIMENSION 18446744073709551615 is too large"
+        );
+        assert_eq!(
+            read(&EUC.replace("DIMENSION : 4", "DIMENSION : 6148914691236517205")).unwrap_err(),
+            "Line 11: invalid number `EOF`"
+        );
+        let explicit = "TYPE : TSP
+DIMENSION : 18446744073709551615
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : FULL_MATRIX
+EDGE_WEIGHT_SECTION
+0 1
+";
+        assert_eq!(
+            read(explicit).unwrap_err(),
+            "EDGE_WEIGHT_SECTION: DIMENSION 18446744073709551615 is too large"
+        );
+        assert_eq!(
+            read(
+                &explicit
+                    .replace("FULL_MATRIX", "UPPER_ROW")
+                    .replace("18446744073709551615", "4294967296")
+            )
+            .unwrap_err(),
+            "EDGE_WEIGHT_SECTION ends after 2 numbers"
+        );
+    }
+
+    #[test]
+    fn test_tours() {
+        let instance = read(EUC).unwrap();
+        let tour = read_tour(
+            "NAME : square.opt.tour\nTYPE : TOUR\nDIMENSION : 4\nTOUR_SECTION\n1\n2\n3\n4\n-1\nEOF\n",
+        )
+        .unwrap();
+        assert_eq!(tour, vec![0, 1, 2, 3]);
+        assert_eq!(instance.tour_length(&tour).unwrap(), 14.0);
+        assert!(instance.tour_length(&[0, 1, 2]).is_err());
+        assert!(instance.tour_length(&[0, 1, 2, 2]).is_err());
+        assert!(instance.tour_length(&[0, 1, 2, 4]).is_err());
+        assert!(read_tour("TOUR_SECTION\n1 2 0 -1\n").is_err());
+        assert!(read_tour("DIMENSION : 3\nTOUR_SECTION\n1 2 -1\n").is_err());
+        assert!(read_tour("DIMENSION : 3\n").is_err());
+    }
+}
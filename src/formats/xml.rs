@@ -0,0 +1,279 @@
+// Just enough XML to read the graph formats built on it: elements,
+// attributes and text, with comments, processing instructions and doctypes
+// skipped.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    /// Text directly inside the element, unescaped, including CDATA.
+    pub text: String,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub fn unescape(text: &str) -> Result<String, String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or(format!("Unterminated entity in: {text}"))?
+            + start;
+        let entity = &rest[start + 1..end];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(decimal) = entity.strip_prefix('#') {
+                    decimal.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or(format!("Unknown entity: &{entity};"))?
+            }
+        };
+        unescaped.push(c);
+        rest = &rest[end + 1..];
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+    fn line(&self) -> usize {
+        self.input[..self.position].matches('\n').count() + 1
+    }
+    fn error(&self, message: &str) -> String {
+        format!("XML line {}: {message}", self.line())
+    }
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+    fn skip_past(&mut self, terminator: &str) -> Result<(), String> {
+        let end = self
+            .rest()
+            .find(terminator)
+            .ok_or_else(|| self.error(&format!("missing `{terminator}`")))?;
+        self.position += end + terminator.len();
+        Ok(())
+    }
+    fn name(&mut self) -> Result<String, String> {
+        let rest = self.rest();
+        let len = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.position += len;
+        Ok(rest[..len].to_string())
+    }
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if !self.rest().starts_with(token) {
+            return Err(self.error(&format!("expected `{token}`")));
+        }
+        self.position += token.len();
+        Ok(())
+    }
+    // Parses the rest of a start tag after its name. Returns whether the
+    // tag closes itself.
+    fn attributes(&mut self, element: &mut Element) -> Result<bool, String> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.position += 2;
+                return Ok(true);
+            }
+            if self.rest().starts_with('>') {
+                self.position += 1;
+                return Ok(false);
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = self
+                .rest()
+                .chars()
+                .next()
+                .filter(|&c| c == '"' || c == '\'')
+                .ok_or_else(|| self.error("expected a quoted attribute value"))?;
+            self.position += 1;
+            let end = self
+                .rest()
+                .find(quote)
+                .ok_or_else(|| self.error("unterminated attribute value"))?;
+            let value = unescape(&self.rest()[..end]).map_err(|e| self.error(&e))?;
+            self.position += end + 1;
+            element.attributes.push((key, value));
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Element, String> {
+    let mut parser = Parser { input, position: 0 };
+    // Open elements, innermost last. Kept explicitly so that deeply nested
+    // documents cannot overflow the call stack.
+    let mut open: Vec<Element> = vec![];
+    let mut root = None;
+    while let Some(next) = parser.rest().find('<') {
+        let text = &parser.rest()[..next];
+        match open.last_mut() {
+            Some(element) => element
+                .text
+                .push_str(&unescape(text).map_err(|e| parser.error(&e))?),
+            None if text.trim().is_empty() => {}
+            None => return Err(parser.error("text outside the root element")),
+        }
+        parser.position += next;
+        let rest = parser.rest();
+        if rest.starts_with("<?") {
+            parser.skip_past("?>")?;
+        } else if rest.starts_with("<!--") {
+            parser.skip_past("-->")?;
+        } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata
+                .find("]]>")
+                .ok_or_else(|| parser.error("missing `]]>`"))?;
+            if let Some(element) = open.last_mut() {
+                element.text.push_str(&cdata[..end]);
+            }
+            parser.position += "<![CDATA[".len() + end + "]]>".len();
+        } else if rest.starts_with("<!") {
+            parser.skip_past(">")?;
+        } else if rest.starts_with("</") {
+            parser.position += 2;
+            let name = parser.name()?;
+            parser.skip_whitespace();
+            parser.expect(">")?;
+            let element = open
+                .pop()
+                .ok_or_else(|| parser.error(&format!("unexpected `</{name}>`")))?;
+            if element.name != name {
+                return Err(parser.error(&format!("`</{name}>` closes `<{}>`", element.name)));
+            }
+            match open.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None => root = Some(element),
+            }
+        } else {
+            if root.is_some() {
+                return Err(parser.error("more than one root element"));
+            }
+            parser.position += 1;
+            let mut element = Element {
+                name: parser.name()?,
+                attributes: vec![],
+                children: vec![],
+                text: String::new(),
+            };
+            let self_closing = parser.attributes(&mut element)?;
+            match (self_closing, open.last_mut()) {
+                (false, _) => open.push(element),
+                (true, Some(parent)) => parent.children.push(element),
+                (true, None) => root = Some(element),
+            }
+        }
+    }
+    if let Some(element) = open.last() {
+        return Err(format!("XML: `<{}>` is never closed", element.name));
+    }
+    if !parser.rest().trim().is_empty() {
+        return Err(parser.error("text outside the root element"));
+    }
+    root.ok_or("XML: missing root element".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = r#"<?xml version="1.0"?>
+<!DOCTYPE root>
+<!-- comment -->
+<root a="1" b='x &amp; &#65;&#x42;'>
+  text &lt; <![CDATA[<raw>]]>
+  <child/>
+  <ns:child c = "2"></ns:child>
+</root>
+"#;
+        let root = parse(input).unwrap();
+        assert_eq!(root.name, "root");
+        assert_eq!(root.attribute("b"), Some("x & AB"));
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.child("ns:child").unwrap().attribute("c"), Some("2"));
+        assert_eq!(root.children_named("child").count(), 1);
+        assert_eq!(
+            root.text.split_whitespace().collect::<Vec<_>>(),
+            ["text", "<", "<raw>"]
+        );
+        assert_eq!(root.child("child").unwrap().text, "");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("<a>").is_err());
+        assert!(parse("<a></b>").is_err());
+        assert!(parse("<a/><b/>").is_err());
+        assert!(parse("<a x=1/>").is_err());
+        assert!(parse("<a x='&bogus;'/>").is_err());
+        assert!(parse("junk<a/>").is_err());
+    }
+
+    #[test]
+    fn test_escape_round_trip() {
+        let text = r#"<"Tom" & 'Jerry'>"#;
+        assert_eq!(unescape(&escape(text)).unwrap(), text);
+    }
+}
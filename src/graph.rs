@@ -1,5 +1,5 @@
 use ordered_float::OrderedFloat;
-use std::{collections::HashMap, hash::Hash};
+use std::collections::HashMap;
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Edge {
@@ -18,7 +18,7 @@ impl Edge {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Node {
     index: usize,
     edges: HashMap<usize, Edge>,
@@ -33,8 +33,14 @@ impl Node {
             label,
         }
     }
+    pub fn index(&self) -> usize {
+        self.index
+    }
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
     pub fn num_edges(&self) -> usize {
-        return self.edges.len();
+        self.edges.len()
     }
     pub fn get_edge(&self, index: usize) -> Option<Edge> {
         self.edges.get(&index).cloned()
@@ -46,28 +52,47 @@ impl Node {
     pub fn remove_edge(&mut self, neighbor: usize) {
         self.edges.remove(&neighbor);
     }
+    pub fn reserve_edges(&mut self, additional: usize) {
+        self.edges.reserve(additional);
+    }
     pub fn get_edge_list(&self) -> Vec<&Edge> {
         self.edges.values().collect()
     }
     pub fn get_ordered_edge_list(&self) -> Vec<&Edge> {
         let mut edges: Vec<_> = self.edges.iter().collect();
-        edges.sort_by(|(x, _), (y, _)| x.cmp(y));
+        edges.sort_by_key(|(x, _)| **x);
         edges.into_iter().map(|(_, e)| e).collect()
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct GraphList {
     pub undirected: bool,
     pub nodes: Vec<Node>,
 }
 
 impl GraphList {
+    pub fn with_nodes(undirected: bool, num_nodes: usize) -> Self {
+        GraphList {
+            undirected,
+            nodes: (0..num_nodes).map(|i| Node::new(i, None)).collect(),
+        }
+    }
+    /// Like `with_nodes`, but fails instead of aborting when there is no room
+    /// for the nodes, for counts read from files.
+    pub fn try_with_nodes(undirected: bool, num_nodes: usize) -> Result<Self, String> {
+        let mut nodes = Vec::new();
+        nodes
+            .try_reserve_exact(num_nodes)
+            .map_err(|_| format!("Not enough memory for {num_nodes} nodes"))?;
+        nodes.extend((0..num_nodes).map(|i| Node::new(i, None)));
+        Ok(GraphList { undirected, nodes })
+    }
     pub fn num_nodes(&self) -> usize {
         self.nodes.len()
     }
     fn valid_indices(&self, from: usize, to: usize) -> bool {
-        ((0..self.num_nodes()).contains(&from) && (0..self.num_nodes()).contains(&to))
+        (0..self.num_nodes()).contains(&from) && (0..self.num_nodes()).contains(&to)
     }
     pub fn get_edge(&self, from: usize, to: usize) -> Result<Option<Edge>, String> {
         if !self.valid_indices(from, to) {
@@ -84,7 +109,19 @@ impl GraphList {
         for node in &self.nodes {
             edges.extend(node.get_edge_list())
         }
-        return edges;
+        edges
+    }
+    /// Like `make_edge_list`, but with each undirected edge listed once,
+    /// from its smaller end.
+    pub fn make_unique_edge_list(&self) -> Vec<&Edge> {
+        self.make_edge_list()
+            .into_iter()
+            .filter(|e| !self.undirected || e.from <= e.to)
+            .collect()
+    }
+    /// Number of edges, counting each undirected edge once.
+    pub fn num_edges(&self) -> usize {
+        self.make_unique_edge_list().len()
     }
     pub fn insert_edge(&mut self, from: usize, to: usize, weight: f64) -> Result<(), String> {
         if !self.valid_indices(from, to) {
@@ -112,20 +149,44 @@ impl GraphList {
         self.nodes.push(Node::new(self.num_nodes(), label));
         self.nodes.last().unwrap()
     }
+    /// Removes a node and its edges. Nodes after it move down one index.
+    pub fn remove_node(&mut self, index: usize) -> Result<Node, String> {
+        if index >= self.num_nodes() {
+            return Err(format!("Node out of range: {index}"));
+        }
+        let removed = self.nodes.remove(index);
+        let shift = |i: usize| if i > index { i - 1 } else { i };
+        for node in &mut self.nodes {
+            node.index = shift(node.index);
+            node.edges = node
+                .edges
+                .drain()
+                .filter(|&(to, _)| to != index)
+                .map(|(to, e)| (shift(to), Edge::new(node.index, shift(to), e.weight.0)))
+                .collect();
+        }
+        Ok(removed)
+    }
 }
 
-pub struct GraphMatrix<const Nodes: usize> {
+pub struct GraphMatrix<const N: usize> {
     undirected: bool,
-    connections: [[OrderedFloat<f64>; Nodes]; Nodes],
+    connections: [[OrderedFloat<f64>; N]; N],
 }
 
-impl<const Nodes: usize> GraphMatrix<Nodes> {
+impl<const N: usize> GraphMatrix<N> {
     pub fn new(undirected: bool) -> Self {
         GraphMatrix {
             undirected,
-            connections: [[OrderedFloat(0.0); Nodes]; Nodes],
+            connections: [[OrderedFloat(0.0); N]; N],
         }
     }
+    pub fn num_nodes(&self) -> usize {
+        N
+    }
+    pub fn is_undirected(&self) -> bool {
+        self.undirected
+    }
     pub fn get_edge(&self, from: usize, to: usize) -> Option<OrderedFloat<f64>> {
         self.connections.get(from)?.get(to).copied()
     }
@@ -135,14 +196,41 @@ impl<const Nodes: usize> GraphMatrix<Nodes> {
         to: usize,
         weight: OrderedFloat<f64>,
     ) -> Result<(), String> {
-        let mut row = self
+        let row = self
             .connections
             .get_mut(from)
             .ok_or(format!("From out of range: {from}"))?;
-        let mut connection = row.get_mut(to).ok_or(format!("To out of range: {to}"))?;
+        let connection = row.get_mut(to).ok_or(format!("To out of range: {to}"))?;
         *connection = weight;
         Ok(())
     }
+    /// Copies `g` into a matrix. Fails if `g` does not have `N` nodes or has
+    /// an edge of weight 0, which the matrix cannot tell from no edge.
+    pub fn from_graph_list(g: &GraphList) -> Result<Self, String> {
+        if g.num_nodes() != N {
+            return Err(format!("Graph has {} nodes, expected {N}", g.num_nodes()));
+        }
+        let mut m = GraphMatrix::new(g.undirected);
+        for e in g.make_edge_list() {
+            if e.weight == OrderedFloat(0.0) {
+                return Err(format!("Edge {} -> {} has weight 0", e.from, e.to));
+            }
+            m.connections[e.from][e.to] = e.weight;
+        }
+        Ok(m)
+    }
+    /// Every nonzero entry becomes an edge.
+    pub fn to_graph_list(&self) -> GraphList {
+        let mut g = GraphList::with_nodes(self.undirected, N);
+        for (i, row) in self.connections.iter().enumerate() {
+            for (j, weight) in row.iter().enumerate() {
+                if *weight != OrderedFloat(0.0) {
+                    g.insert_edge(i, j, weight.0).unwrap();
+                }
+            }
+        }
+        g
+    }
 }
 
 #[cfg(test)]
@@ -218,6 +306,39 @@ mod test {
         assert_eq!(new_node.label, Some("C".to_string()));
     }
 
+    #[test]
+    fn test_num_edges() {
+        let mut graph = GraphList::with_nodes(true, 3);
+        graph.insert_edge(0, 1, 1.0).unwrap();
+        graph.insert_edge(2, 1, 1.0).unwrap();
+        graph.insert_edge(2, 2, 1.0).unwrap();
+        assert_eq!(graph.make_edge_list().len(), 5);
+        assert_eq!(graph.num_edges(), 3);
+        assert!(graph.make_unique_edge_list().iter().all(|e| e.from <= e.to));
+        graph.undirected = false;
+        assert_eq!(graph.num_edges(), 5);
+    }
+
+    #[test]
+    fn test_remove_node() {
+        let mut graph = GraphList::with_nodes(true, 0);
+        for label in ["A", "B", "C"] {
+            graph.insert_node(Some(label.to_string()));
+        }
+        graph.insert_edge(0, 1, 1.0).unwrap();
+        graph.insert_edge(1, 2, 2.0).unwrap();
+        graph.insert_edge(2, 0, 3.0).unwrap();
+
+        let removed = graph.remove_node(1).unwrap();
+        assert_eq!(removed.label(), Some("B"));
+        assert_eq!(graph.num_nodes(), 2);
+        assert_eq!(graph.nodes[1].index(), 1);
+        assert_eq!(graph.nodes[1].label(), Some("C"));
+        assert_eq!(graph.make_edge_list().len(), 2);
+        assert_eq!(graph.get_edge(1, 0).unwrap(), Some(Edge::new(1, 0, 3.0)));
+        assert!(graph.remove_node(2).is_err());
+    }
+
     #[test]
     fn test_undirected_graph() {
         let mut graph = GraphList {
@@ -247,4 +368,27 @@ mod test {
         assert!(matrix.set_edge(3, 0, OrderedFloat(1.0)).is_err());
         assert_eq!(matrix.get_edge(3, 0), None);
     }
+
+    #[test]
+    fn test_graph_matrix_conversions() {
+        let mut graph = GraphList::with_nodes(true, 3);
+        graph.insert_edge(0, 1, 2.5).unwrap();
+        graph.insert_edge(2, 2, -1.0).unwrap();
+        let matrix: GraphMatrix<3> = GraphMatrix::from_graph_list(&graph).unwrap();
+        assert!(matrix.is_undirected());
+        assert_eq!(matrix.get_edge(1, 0), Some(OrderedFloat(2.5)));
+        assert_eq!(matrix.get_edge(2, 2), Some(OrderedFloat(-1.0)));
+
+        let back = matrix.to_graph_list();
+        assert!(back.undirected);
+        assert_eq!(back.make_edge_list().len(), 3);
+        assert_eq!(
+            back.get_edge(0, 1).unwrap().unwrap().weight,
+            OrderedFloat(2.5)
+        );
+
+        assert!(GraphMatrix::<2>::from_graph_list(&graph).is_err());
+        graph.insert_edge(0, 2, 0.0).unwrap();
+        assert!(GraphMatrix::<3>::from_graph_list(&graph).is_err());
+    }
 }
@@ -0,0 +1,324 @@
+use crate::graph::GraphList;
+use crate::topological::topological_sort;
+use std::collections::HashMap;
+
+// Node placement for drawing graphs. Coordinates are in arbitrary units,
+// renderers scale them to fit their canvas.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Layout {
+    pub positions: Vec<Point>,
+    /// Intermediate points of edges that should not be drawn as a straight
+    /// line, keyed by `(from, to)`.
+    pub edge_bends: HashMap<(usize, usize), Vec<Point>>,
+}
+
+/// Places the nodes evenly on the unit circle, in index order.
+pub fn circular_layout(g: &GraphList) -> Layout {
+    let n = g.num_nodes();
+    let positions = (0..n)
+        .map(|i| {
+            let angle = 2.0 * std::f64::consts::PI * i as f64 / n as f64;
+            Point::new(angle.cos(), angle.sin())
+        })
+        .collect();
+    Layout {
+        positions,
+        edge_bends: HashMap::new(),
+    }
+}
+
+/// Fruchterman-Reingold spring embedding: edges (regardless of direction)
+/// pull their endpoints together while all nodes repel each other. Starts
+/// from the circular layout, so the result is deterministic.
+pub fn force_directed_layout(g: &GraphList, iterations: usize) -> Layout {
+    let n = g.num_nodes();
+    let mut positions = circular_layout(g).positions;
+    if n < 2 {
+        return Layout {
+            positions,
+            edge_bends: HashMap::new(),
+        };
+    }
+    // Nudge the starting positions so that symmetric graphs can unfold.
+    for (i, p) in positions.iter_mut().enumerate() {
+        p.x += 0.01 * ((i * 7919) % 13) as f64 / 13.0;
+        p.y += 0.01 * ((i * 104_729) % 17) as f64 / 17.0;
+    }
+    let edges: Vec<(usize, usize)> = g
+        .make_edge_list()
+        .into_iter()
+        .filter(|e| e.from != e.to)
+        .map(|e| (e.from, e.to))
+        .collect();
+    // Ideal edge length for nodes spread over an area of 4 (the 2x2 square
+    // around the unit circle).
+    let k = (4.0 / n as f64).sqrt();
+    let initial_temperature = 0.2;
+    for iteration in 0..iterations {
+        let temperature = initial_temperature * (1.0 - iteration as f64 / iterations as f64);
+        let mut displacement = vec![Point::new(0.0, 0.0); n];
+        for i in 0..n {
+            for j in i + 1..n {
+                let dx = positions[i].x - positions[j].x;
+                let dy = positions[i].y - positions[j].y;
+                let distance = (dx * dx + dy * dy).sqrt().max(1e-9);
+                let force = k * k / distance;
+                let (fx, fy) = (dx / distance * force, dy / distance * force);
+                displacement[i].x += fx;
+                displacement[i].y += fy;
+                displacement[j].x -= fx;
+                displacement[j].y -= fy;
+            }
+        }
+        for &(from, to) in &edges {
+            let dx = positions[from].x - positions[to].x;
+            let dy = positions[from].y - positions[to].y;
+            let distance = (dx * dx + dy * dy).sqrt().max(1e-9);
+            let force = distance * distance / k;
+            let (fx, fy) = (dx / distance * force, dy / distance * force);
+            displacement[from].x -= fx;
+            displacement[from].y -= fy;
+            displacement[to].x += fx;
+            displacement[to].y += fy;
+        }
+        for (p, d) in positions.iter_mut().zip(&displacement) {
+            let length = (d.x * d.x + d.y * d.y).sqrt();
+            if length > 0.0 {
+                let step = length.min(temperature);
+                p.x += d.x / length * step;
+                p.y += d.y / length * step;
+            }
+        }
+    }
+    Layout {
+        positions,
+        edge_bends: HashMap::new(),
+    }
+}
+
+// Longest-path layering: sources are on layer 0 and every node sits one
+// layer below its deepest predecessor. Self-loops are ignored.
+fn longest_path_layers(g: &GraphList) -> Result<Vec<usize>, String> {
+    let mut acyclic = g.clone();
+    for (i, node) in acyclic.nodes.iter_mut().enumerate() {
+        node.remove_edge(i);
+    }
+    let order = topological_sort(&acyclic).map_err(|cycle| {
+        let cycle: Vec<String> = cycle.iter().map(usize::to_string).collect();
+        format!(
+            "Layered layout needs a directed acyclic graph, found the cycle {}",
+            cycle.join(" -> ")
+        )
+    })?;
+    let mut layer = vec![0; g.num_nodes()];
+    for node in order {
+        for e in acyclic.nodes[node].get_edge_list() {
+            layer[e.to] = layer[e.to].max(layer[node] + 1);
+        }
+    }
+    Ok(layer)
+}
+
+// Orders each layer by the average position of the neighbors in the
+// previous layer.
+fn barycenter_sweep(layers: &mut [Vec<usize>], neighbors: &[Vec<usize>], position: &mut [f64]) {
+    for layer in layers.iter_mut() {
+        let mut keyed: Vec<(f64, usize)> = layer
+            .iter()
+            .map(|&v| {
+                let adjacent = &neighbors[v];
+                let key = if adjacent.is_empty() {
+                    position[v]
+                } else {
+                    adjacent.iter().map(|&u| position[u]).sum::<f64>() / adjacent.len() as f64
+                };
+                (key, v)
+            })
+            .collect();
+        keyed.sort_by(|a, b| {
+            a.0.total_cmp(&b.0)
+                .then(position[a.1].total_cmp(&position[b.1]))
+        });
+        for (i, &(_, v)) in keyed.iter().enumerate() {
+            position[v] = i as f64;
+        }
+        *layer = keyed.into_iter().map(|(_, v)| v).collect();
+    }
+}
+
+/// Sugiyama-style layered drawing of a DAG, top to bottom: nodes are
+/// assigned to layers by longest path, edges spanning several layers get a
+/// bend on every layer they cross, and nodes within each layer are ordered
+/// with barycenter sweeps to reduce crossings. Fails if the graph has a
+/// cycle; undirected edges count as a cycle of two arcs.
+pub fn layered_layout(g: &GraphList) -> Result<Layout, String> {
+    let n = g.num_nodes();
+    let layer_of = longest_path_layers(g)?;
+
+    // Split long edges into chains of virtual nodes, one per crossed layer.
+    let mut layer_of_all = layer_of.clone();
+    let mut chains: Vec<((usize, usize), Vec<usize>)> = vec![];
+    let mut up: Vec<Vec<usize>> = vec![vec![]; n];
+    let mut down: Vec<Vec<usize>> = vec![vec![]; n];
+    for e in g.make_edge_list() {
+        if e.from == e.to {
+            continue;
+        }
+        let mut previous = e.from;
+        let mut chain = vec![];
+        for layer in layer_of[e.from] + 1..layer_of[e.to] {
+            let virtual_node = layer_of_all.len();
+            layer_of_all.push(layer);
+            up.push(vec![]);
+            down.push(vec![]);
+            chain.push(virtual_node);
+            down[previous].push(virtual_node);
+            up[virtual_node].push(previous);
+            previous = virtual_node;
+        }
+        down[previous].push(e.to);
+        up[e.to].push(previous);
+        if !chain.is_empty() {
+            chains.push(((e.from, e.to), chain));
+        }
+    }
+
+    let num_layers = layer_of.iter().max().map_or(0, |&l| l + 1);
+    let mut layers: Vec<Vec<usize>> = vec![vec![]; num_layers];
+    let mut position = vec![0.0; layer_of_all.len()];
+    for (v, &layer) in layer_of_all.iter().enumerate() {
+        position[v] = layers[layer].len() as f64;
+        layers[layer].push(v);
+    }
+    for _ in 0..4 {
+        barycenter_sweep(&mut layers, &up, &mut position);
+        layers.reverse();
+        barycenter_sweep(&mut layers, &down, &mut position);
+        layers.reverse();
+    }
+
+    let mut points = vec![Point::new(0.0, 0.0); layer_of_all.len()];
+    for (y, layer) in layers.iter().enumerate() {
+        let offset = (layer.len() as f64 - 1.0) / 2.0;
+        for (x, &v) in layer.iter().enumerate() {
+            points[v] = Point::new(x as f64 - offset, y as f64);
+        }
+    }
+    let edge_bends = chains
+        .into_iter()
+        .map(|(edge, chain)| (edge, chain.iter().map(|&v| points[v]).collect()))
+        .collect();
+    points.truncate(n);
+    Ok(Layout {
+        positions: points,
+        edge_bends,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: Point, b: Point) -> f64 {
+        ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn test_circular_layout() {
+        let layout = circular_layout(&GraphList::with_nodes(false, 4));
+        let expected = [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)];
+        for (p, (x, y)) in layout.positions.iter().zip(expected) {
+            assert!((p.x - x).abs() < 1e-9 && (p.y - y).abs() < 1e-9);
+        }
+        assert!(circular_layout(&GraphList::with_nodes(false, 0))
+            .positions
+            .is_empty());
+    }
+
+    #[test]
+    fn test_force_directed_layout() {
+        // Two triangles joined by a single edge.
+        let mut graph = GraphList::with_nodes(true, 6);
+        for (a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+            graph.insert_edge(a, b, 1.0).unwrap();
+        }
+        let layout = force_directed_layout(&graph, 200);
+        assert_eq!(layout, force_directed_layout(&graph, 200));
+        let p = &layout.positions;
+        assert!(p.iter().all(|p| p.x.is_finite() && p.y.is_finite()));
+        // Nodes within a triangle end up closer than nodes across triangles.
+        assert!(distance(p[0], p[1]) < distance(p[0], p[4]));
+        assert!(distance(p[3], p[5]) < distance(p[5], p[1]));
+        // No two nodes on top of each other.
+        for i in 0..6 {
+            for j in i + 1..6 {
+                assert!(distance(p[i], p[j]) > 0.05);
+            }
+        }
+    }
+
+    #[test]
+    fn test_layered_layout() {
+        //   0
+        //  / \
+        // 1   2
+        //  \ / \
+        //   3   |
+        //    \ /
+        //     4
+        let mut graph = GraphList::with_nodes(false, 5);
+        for (a, b) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (2, 4)] {
+            graph.insert_edge(a, b, 1.0).unwrap();
+        }
+        let layout = layered_layout(&graph).unwrap();
+        let y: Vec<f64> = layout.positions.iter().map(|p| p.y).collect();
+        assert_eq!(y, vec![0.0, 1.0, 1.0, 2.0, 3.0]);
+        // 2 -> 4 spans two layers and bends once, on layer 2.
+        assert_eq!(layout.edge_bends.len(), 1);
+        let bends = &layout.edge_bends[&(2, 4)];
+        assert_eq!(bends.len(), 1);
+        assert_eq!(bends[0].y, 2.0);
+        // The bend shares layer 2 with node 3 without overlapping it.
+        assert_ne!(bends[0].x, layout.positions[3].x);
+    }
+
+    #[test]
+    fn test_layered_layout_reduces_crossings() {
+        // 0 -> 3 and 1 -> 2 cross when each layer keeps index order.
+        let mut graph = GraphList::with_nodes(false, 4);
+        graph.insert_edge(0, 3, 1.0).unwrap();
+        graph.insert_edge(1, 2, 1.0).unwrap();
+        let p = layered_layout(&graph).unwrap().positions;
+        assert_eq!(p[0].y, p[1].y);
+        assert_eq!(p[2].y, p[3].y);
+        assert_eq!(p[0].x < p[1].x, p[3].x < p[2].x);
+    }
+
+    #[test]
+    fn test_layered_layout_rejects_cycles() {
+        let mut graph = GraphList::with_nodes(false, 2);
+        graph.insert_edge(0, 1, 1.0).unwrap();
+        graph.insert_edge(1, 0, 1.0).unwrap();
+        assert_eq!(
+            layered_layout(&graph).unwrap_err(),
+            "Layered layout needs a directed acyclic graph, found the cycle 0 -> 1"
+        );
+
+        let mut self_loop = GraphList::with_nodes(false, 1);
+        self_loop.insert_edge(0, 0, 1.0).unwrap();
+        assert!(layered_layout(&self_loop).is_ok());
+    }
+}
@@ -1,9 +1,30 @@
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match cli::run(&args) {
+        Ok(output) => print!("{output}"),
+        Err(error) => {
+            eprintln!("algo3: {error}");
+            std::process::exit(1);
+        }
+    }
 }
 pub mod bellman_ford;
 pub mod bfs;
+pub mod bipartite;
+pub mod cli;
+pub mod components;
+pub mod convert;
 pub mod dfs;
 pub mod dijkstra;
 pub mod floyd_warshall;
+pub mod formats;
 pub mod graph;
+pub mod layout;
+pub mod paths;
+pub mod repl;
+pub mod scc;
+pub mod server;
+pub mod stats;
+pub mod topological;
+pub mod union_find;
+pub mod visitor;
@@ -0,0 +1,291 @@
+use crate::bellman_ford::bellman_ford;
+use crate::bfs::{bfs, BfsTree};
+use crate::dfs::dfs_stack;
+use crate::dijkstra::dijkstra;
+use crate::floyd_warshall::floyd_warshall;
+use crate::graph::GraphList;
+use ordered_float::OrderedFloat;
+use std::collections::VecDeque;
+
+// Paths out of the predecessor arrays the search algorithms return, where
+// -1 stands for no predecessor.
+
+/// Walks predecessors back from a node towards the start of its path,
+/// yielding the node first. Stops after as many nodes as the graph has, so
+/// a cycle of predecessors cannot make it loop forever.
+pub struct Ancestors<'a> {
+    parent: &'a [Option<usize>],
+    next: Option<usize>,
+    stop: Option<usize>,
+    remaining: usize,
+}
+
+impl Iterator for Ancestors<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let node = self.next?;
+        self.remaining = self.remaining.checked_sub(1)?;
+        self.next = if Some(node) == self.stop {
+            None
+        } else {
+            self.parent[node]
+        };
+        Some(node)
+    }
+}
+
+fn predecessors<T: Copy + TryInto<usize>>(parent: &[T]) -> Vec<Option<usize>> {
+    parent.iter().map(|&p| p.try_into().ok()).collect()
+}
+
+/// Parent links of a search from one or more sources.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShortestPathTree {
+    sources: Vec<usize>,
+    parent: Vec<Option<usize>>,
+}
+
+impl ShortestPathTree {
+    /// Wraps the parent array of a search from `source`, such as the result
+    /// of `bfs` or `dfs_stack`.
+    pub fn from_parents<T: Copy + TryInto<usize>>(source: usize, parent: &[T]) -> Self {
+        let mut parent = predecessors(parent);
+        parent[source] = None;
+        ShortestPathTree {
+            sources: vec![source],
+            parent,
+        }
+    }
+
+    /// Builds the tree from shortest distances from `source`, as returned by
+    /// `dijkstra` or `bellman_ford`: the parent of a node is the start of an
+    /// edge into it whose weight is exactly the difference in distances.
+    pub fn from_distances(g: &GraphList, source: usize, distance: &[OrderedFloat<f64>]) -> Self {
+        let mut parent = vec![None; g.num_nodes()];
+        let mut seen = vec![false; g.num_nodes()];
+        seen[source] = true;
+        // Searching along such edges only keeps zero-weight cycles out of
+        // the tree.
+        let mut pending = VecDeque::from([source]);
+        while let Some(node) = pending.pop_front() {
+            for e in g.nodes[node].get_ordered_edge_list() {
+                if !seen[e.to] && distance[node] + e.weight == distance[e.to] {
+                    seen[e.to] = true;
+                    parent[e.to] = Some(node);
+                    pending.push_back(e.to);
+                }
+            }
+        }
+        ShortestPathTree {
+            sources: vec![source],
+            parent,
+        }
+    }
+
+    pub fn sources(&self) -> &[usize] {
+        &self.sources
+    }
+
+    pub fn parent(&self, node: usize) -> Option<usize> {
+        self.parent[node]
+    }
+
+    pub fn reaches(&self, node: usize) -> bool {
+        self.parent[node].is_some() || self.sources.contains(&node)
+    }
+
+    /// The path to `target` backwards, empty if `target` is not reached.
+    pub fn ancestors(&self, target: usize) -> Ancestors<'_> {
+        Ancestors {
+            parent: &self.parent,
+            next: self.reaches(target).then_some(target),
+            stop: None,
+            remaining: self.parent.len(),
+        }
+    }
+
+    /// The path from a source to `target`, or None if `target` is not
+    /// reached.
+    pub fn path_to(&self, target: usize) -> Option<Vec<usize>> {
+        let mut path: Vec<usize> = self.ancestors(target).collect();
+        if !path.last().is_some_and(|node| self.sources.contains(node)) {
+            return None;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl From<&BfsTree> for ShortestPathTree {
+    fn from(tree: &BfsTree) -> Self {
+        ShortestPathTree {
+            sources: (0..tree.distance.len())
+                .filter(|&i| tree.distance[i] == Some(0))
+                .collect(),
+            parent: tree.parent.clone(),
+        }
+    }
+}
+
+/// The last step of the shortest path between every pair of nodes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PredecessorMatrix {
+    last: Vec<Vec<Option<usize>>>,
+}
+
+impl PredecessorMatrix {
+    /// Wraps the result of `floyd_warshall`.
+    pub fn from_floyd_warshall(last: &[Vec<isize>]) -> Self {
+        PredecessorMatrix {
+            last: last.iter().map(|row| predecessors(row)).collect(),
+        }
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.last.len()
+    }
+
+    /// The node before `to` on the shortest path from `from`.
+    pub fn predecessor(&self, from: usize, to: usize) -> Option<usize> {
+        self.last[from][to]
+    }
+
+    /// The path from `from` to `to` backwards, ending at `from` unless `to`
+    /// cannot be reached.
+    pub fn ancestors(&self, from: usize, to: usize) -> Ancestors<'_> {
+        Ancestors {
+            parent: &self.last[from],
+            next: Some(to),
+            stop: Some(from),
+            remaining: self.last.len(),
+        }
+    }
+
+    /// The shortest path from `from` to `to`, or None if there is none.
+    pub fn path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut path: Vec<usize> = self.ancestors(from, to).collect();
+        if path.last() != Some(&from) {
+            return None;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The shortest paths from `source`.
+    pub fn tree(&self, source: usize) -> ShortestPathTree {
+        let mut parent = self.last[source].clone();
+        parent[source] = None;
+        ShortestPathTree {
+            sources: vec![source],
+            parent,
+        }
+    }
+}
+
+pub fn bfs_tree(g: &GraphList, start: usize) -> ShortestPathTree {
+    ShortestPathTree::from_parents(start, &bfs(g, start))
+}
+
+pub fn dfs_tree(g: &GraphList, start: usize) -> ShortestPathTree {
+    ShortestPathTree::from_parents(start, &dfs_stack(g, start))
+}
+
+/// Dijkstra's distances from `start` and their tree. Weights must not be
+/// negative.
+pub fn dijkstra_tree(g: &GraphList, start: usize) -> (Vec<OrderedFloat<f64>>, ShortestPathTree) {
+    let distance = dijkstra(g, start);
+    let tree = ShortestPathTree::from_distances(g, start, &distance);
+    (distance, tree)
+}
+
+/// Like `dijkstra_tree` allowing negative weights, or None if a negative
+/// cycle is reachable from `start`.
+pub fn bellman_ford_tree(
+    g: &GraphList,
+    start: usize,
+) -> Option<(Vec<OrderedFloat<f64>>, ShortestPathTree)> {
+    let distance = bellman_ford(g, start)?;
+    let tree = ShortestPathTree::from_distances(g, start, &distance);
+    Some((distance, tree))
+}
+
+pub fn floyd_warshall_paths(g: &GraphList) -> PredecessorMatrix {
+    PredecessorMatrix::from_floyd_warshall(&floyd_warshall(g))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bfs::multi_source_bfs;
+
+    fn create_test_graph() -> GraphList {
+        let mut graph = GraphList::with_nodes(false, 5);
+        graph.insert_edge(0, 1, 4.0).unwrap();
+        graph.insert_edge(0, 2, 1.0).unwrap();
+        graph.insert_edge(2, 1, 2.0).unwrap();
+        graph.insert_edge(1, 3, 1.0).unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_search_trees() {
+        let g = create_test_graph();
+        let tree = bfs_tree(&g, 0);
+        assert_eq!(tree.sources(), [0]);
+        assert_eq!(tree.path_to(3), Some(vec![0, 1, 3]));
+        assert_eq!(tree.path_to(0), Some(vec![0]));
+        assert_eq!(tree.path_to(4), None);
+        assert_eq!(tree.ancestors(3).collect::<Vec<_>>(), vec![3, 1, 0]);
+        assert_eq!(tree.ancestors(4).count(), 0);
+        assert!(!tree.reaches(4));
+
+        assert_eq!(dfs_tree(&g, 0).path_to(3), Some(vec![0, 1, 3]));
+        assert_eq!(dfs_tree(&g, 2).path_to(0), None);
+    }
+
+    #[test]
+    fn test_from_distances() {
+        let mut g = create_test_graph();
+        let (distance, tree) = dijkstra_tree(&g, 0);
+        assert_eq!(distance[3], OrderedFloat(4.0));
+        assert_eq!(tree.path_to(3), Some(vec![0, 2, 1, 3]));
+        assert_eq!(tree.parent(0), None);
+
+        // Zero-weight cycles do not end up in the tree.
+        g.insert_edge(3, 4, 0.0).unwrap();
+        g.insert_edge(4, 3, 0.0).unwrap();
+        let (_, tree) = dijkstra_tree(&g, 0);
+        assert_eq!(tree.path_to(4), Some(vec![0, 2, 1, 3, 4]));
+
+        g.insert_edge(2, 4, -5.0).unwrap();
+        let (distance, tree) = bellman_ford_tree(&g, 0).unwrap();
+        assert_eq!(distance[3], OrderedFloat(-4.0));
+        assert_eq!(tree.path_to(3), Some(vec![0, 2, 4, 3]));
+        g.insert_edge(3, 2, 1.0).unwrap();
+        assert!(bellman_ford_tree(&g, 0).is_none());
+    }
+
+    #[test]
+    fn test_predecessor_matrix() {
+        let paths = floyd_warshall_paths(&create_test_graph());
+        assert_eq!(paths.num_nodes(), 5);
+        assert_eq!(paths.path(0, 3), Some(vec![0, 2, 1, 3]));
+        assert_eq!(paths.path(2, 2), Some(vec![2]));
+        assert_eq!(paths.path(3, 0), None);
+        assert_eq!(paths.predecessor(0, 1), Some(2));
+        assert_eq!(paths.ancestors(0, 3).collect::<Vec<_>>(), vec![3, 1, 2, 0]);
+        assert_eq!(paths.tree(0).path_to(1), Some(vec![0, 2, 1]));
+        assert_eq!(paths.tree(1).path_to(0), None);
+    }
+
+    #[test]
+    fn test_from_bfs_tree() {
+        let g = create_test_graph();
+        let tree = ShortestPathTree::from(&multi_source_bfs(&g, &[2, 4], None));
+        assert_eq!(tree.sources(), [2, 4]);
+        assert_eq!(tree.path_to(3), Some(vec![2, 1, 3]));
+        assert_eq!(tree.path_to(4), Some(vec![4]));
+        assert_eq!(tree.path_to(0), None);
+    }
+}
@@ -0,0 +1,408 @@
+use crate::bfs::multi_source_bfs;
+use crate::cli::{execute, resolve_node, Options, OutputFormat, Table};
+use crate::formats::file::Format;
+use crate::graph::GraphList;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+// An interactive session on one graph. Every line is a command; changes to
+// the graph can be undone and the graph saved back to disk.
+
+pub const HELP: &str = "\
+Commands:
+  info                          size and direction of the graph
+  nodes                         list the nodes
+  edges                         list the edges
+  neighbors <node> [depth]      nodes up to depth (default 1) edges away
+  add-node [label]              add a node
+  remove-node <node>            remove a node, renumbering the later ones
+  add-edge <from> <to> [weight] add or reweigh an edge (default weight 1)
+  remove-edge <from> <to>       remove an edge
+  run <command> [source] [target]
+                                run bfs, dfs, components, scc, topo, stats,
+                                bipartite, dijkstra, bellman-ford or
+                                floyd-warshall
+  path <from> <to> [command]    shortest path, by dijkstra unless given
+  undo                          undo the last change
+  load <file>                   replace the graph with a file
+  save [file] [format]          save the graph, by default where it was loaded
+  help                          print this help
+  quit                          leave
+
+Nodes are given by label or index.
+";
+
+/// Number of changes that can be undone.
+const UNDO_LIMIT: usize = 100;
+
+pub struct Session {
+    pub graph: GraphList,
+    /// Where the graph was loaded from or last saved to.
+    pub path: Option<String>,
+    history: Vec<GraphList>,
+}
+
+fn node_value(g: &GraphList, i: usize) -> Value {
+    match g.nodes[i].label() {
+        Some(label) => json!(label),
+        None => json!(i),
+    }
+}
+
+fn weight(text: Option<&&str>) -> Result<f64, String> {
+    text.map_or(Ok(1.0), |w| {
+        w.parse().map_err(|_| format!("Invalid weight: {w}"))
+    })
+}
+
+impl Session {
+    pub fn new(graph: GraphList, path: Option<String>) -> Self {
+        Session {
+            graph,
+            path,
+            history: vec![],
+        }
+    }
+
+    // Applies `change`, keeping the previous graph for `undo`. A failed
+    // change leaves the graph as it was.
+    fn change(
+        &mut self,
+        change: impl FnOnce(&mut GraphList) -> Result<String, String>,
+    ) -> Result<String, String> {
+        let previous = self.graph.clone();
+        match change(&mut self.graph) {
+            Ok(message) => {
+                if self.history.len() == UNDO_LIMIT {
+                    self.history.remove(0);
+                }
+                self.history.push(previous);
+                Ok(message)
+            }
+            Err(error) => {
+                self.graph = previous;
+                Err(error)
+            }
+        }
+    }
+
+    fn neighbors(&self, node: usize, depth: usize) -> Table {
+        let g = &self.graph;
+        let tree = multi_source_bfs(g, &[node], Some(depth));
+        let rows = tree
+            .levels()
+            .iter()
+            .enumerate()
+            .skip(1)
+            .flat_map(|(d, level)| level.iter().map(move |&i| (d, i)))
+            .map(|(d, i)| {
+                vec![
+                    json!(i),
+                    node_value(g, i),
+                    json!(d),
+                    node_value(g, tree.parent[i].unwrap()),
+                ]
+            })
+            .collect();
+        Table {
+            columns: vec!["node", "name", "depth", "via"],
+            rows,
+        }
+    }
+
+    /// Runs one command line, returning what to print.
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = tokens.split_first() else {
+            return Ok(String::new());
+        };
+        let node = |i: usize| -> Result<usize, String> {
+            let name = args
+                .get(i)
+                .ok_or(format!("{command}: missing node\n\n{HELP}"))?;
+            resolve_node(&self.graph, name)
+        };
+        let render = |table: Table| table.render(OutputFormat::Table);
+        match command {
+            "help" => Ok(HELP.to_string()),
+            "info" => Ok(format!(
+                "{} graph with {} nodes and {} edges{}\n",
+                if self.graph.undirected {
+                    "Undirected"
+                } else {
+                    "Directed"
+                },
+                self.graph.num_nodes(),
+                self.graph.num_edges(),
+                self.path
+                    .as_ref()
+                    .map_or(String::new(), |path| format!(", from {path}"))
+            )),
+            "nodes" => Ok(render(Table {
+                columns: vec!["node", "label", "degree"],
+                rows: self
+                    .graph
+                    .nodes
+                    .iter()
+                    .map(|n| {
+                        vec![
+                            json!(n.index()),
+                            n.label().map_or(Value::Null, |l| json!(l)),
+                            json!(n.num_edges()),
+                        ]
+                    })
+                    .collect(),
+            })),
+            "edges" => {
+                let g = &self.graph;
+                let mut edges = g.make_unique_edge_list();
+                edges.sort_by_key(|e| (e.from, e.to));
+                Ok(render(Table {
+                    columns: vec!["from", "to", "weight"],
+                    rows: edges
+                        .iter()
+                        .map(|e| {
+                            vec![
+                                node_value(g, e.from),
+                                node_value(g, e.to),
+                                json!(e.weight.0),
+                            ]
+                        })
+                        .collect(),
+                }))
+            }
+            "neighbors" => {
+                let depth = match args.get(1) {
+                    Some(d) => d.parse().map_err(|_| format!("Invalid depth: {d}"))?,
+                    None => 1,
+                };
+                Ok(render(self.neighbors(node(0)?, depth)))
+            }
+            "add-node" => {
+                let label = (!args.is_empty()).then(|| args.join(" "));
+                self.change(|g| {
+                    let node = g.insert_node(label);
+                    Ok(format!("Added node {}\n", node.index()))
+                })
+            }
+            "remove-node" => {
+                let index = node(0)?;
+                self.change(|g| {
+                    g.remove_node(index)?;
+                    Ok(format!("Removed node {index}\n"))
+                })
+            }
+            "add-edge" => {
+                let (from, to, weight) = (node(0)?, node(1)?, weight(args.get(2))?);
+                self.change(|g| {
+                    g.insert_edge(from, to, weight)?;
+                    Ok(format!("Added edge {from} -> {to} with weight {weight}\n"))
+                })
+            }
+            "remove-edge" => {
+                let (from, to) = (node(0)?, node(1)?);
+                if !self.graph.is_edge(from, to) {
+                    return Err(format!("No edge {from} -> {to}"));
+                }
+                self.change(|g| {
+                    g.remove_edge(from, to)?;
+                    Ok(format!("Removed edge {from} -> {to}\n"))
+                })
+            }
+            "run" | "path" => {
+                let mut options = Options::default();
+                if command == "run" {
+                    let (name, rest) = args
+                        .split_first()
+                        .ok_or(format!("run: missing command\n\n{HELP}"))?;
+                    options.command = name.to_string();
+                    options.source = rest.first().map(|s| s.to_string());
+                    options.target = rest.get(1).map(|s| s.to_string());
+                } else {
+                    let [from, to, rest @ ..] = args else {
+                        return Err(format!("path: expected two nodes\n\n{HELP}"));
+                    };
+                    options.command = rest.first().unwrap_or(&"dijkstra").to_string();
+                    options.source = Some(from.to_string());
+                    options.target = Some(to.to_string());
+                }
+                Ok(render(execute(&options, &self.graph)?))
+            }
+            "undo" => {
+                let previous = self.history.pop().ok_or("Nothing to undo")?;
+                self.graph = previous;
+                Ok("Undone\n".to_string())
+            }
+            "load" => {
+                let path = args.first().ok_or("load: missing file")?.to_string();
+                let (graph, format) = GraphList::load(&path)?;
+                let message = format!("Loaded {path} ({format}): {} nodes\n", graph.num_nodes());
+                self.change(|g| {
+                    *g = graph;
+                    Ok(message)
+                })
+                .inspect(|_| self.path = Some(path))
+            }
+            "save" => {
+                let path = args
+                    .first()
+                    .map(|p| p.to_string())
+                    .or(self.path.clone())
+                    .ok_or("save: missing file")?;
+                let format = match args.get(1) {
+                    Some(name) => {
+                        Format::from_name(name).ok_or(format!("Unknown format: {name}"))?
+                    }
+                    None => Format::from_extension(path.as_ref()).ok_or(format!(
+                        "Cannot tell the format of {path} from its extension"
+                    ))?,
+                };
+                self.graph.save(&path, format)?;
+                let message = format!("Saved {path} ({format})\n");
+                self.path = Some(path);
+                Ok(message)
+            }
+            _ => Err(format!("Unknown command: {command}. Type `help` for help.")),
+        }
+    }
+}
+
+/// Reads commands from `input` until `quit` or the end of the input,
+/// writing results and errors to `output`.
+pub fn run(session: &mut Session, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    write!(output, "> ")?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        if matches!(line.trim(), "quit" | "exit") {
+            return Ok(());
+        }
+        match session.execute(&line) {
+            Ok(text) => write!(output, "{text}")?,
+            Err(error) => writeln!(output, "error: {error}")?,
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    writeln!(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_session() -> Session {
+        let mut session = Session::new(GraphList::with_nodes(false, 0), None);
+        for line in [
+            "add-node a",
+            "add-node b",
+            "add-node",
+            "add-edge a b 4",
+            "add-edge a 2",
+            "add-edge 2 b 2",
+        ] {
+            session.execute(line).unwrap();
+        }
+        session
+    }
+
+    #[test]
+    fn test_edit_and_undo() {
+        let mut session = create_session();
+        assert_eq!(session.graph.num_nodes(), 3);
+        assert_eq!(
+            session.execute("info").unwrap(),
+            "Directed graph with 3 nodes and 3 edges\n"
+        );
+        assert_eq!(
+            session.execute("remove-node a").unwrap(),
+            "Removed node 0\n"
+        );
+        assert_eq!(session.graph.num_nodes(), 2);
+        assert!(session.graph.is_edge(1, 0));
+        assert!(session.execute("remove-edge 0 1").is_err());
+        session.execute("remove-edge 1 0").unwrap();
+        assert!(session.graph.make_edge_list().is_empty());
+
+        session.execute("undo").unwrap();
+        session.execute("undo").unwrap();
+        assert_eq!(session.graph.num_nodes(), 3);
+        assert_eq!(session.graph.nodes[0].label(), Some("a"));
+        for _ in 0..6 {
+            session.execute("undo").unwrap();
+        }
+        assert_eq!(session.graph.num_nodes(), 0);
+        assert!(session.execute("undo").is_err());
+    }
+
+    #[test]
+    fn test_errors_leave_graph_unchanged() {
+        let mut session = create_session();
+        assert!(session.execute("add-edge a c").is_err());
+        assert!(session.execute("add-edge a b heavy").is_err());
+        assert!(session.execute("remove-node").is_err());
+        assert!(session.execute("frobnicate").is_err());
+        assert_eq!(session.execute("").unwrap(), "");
+        session.execute("undo").unwrap();
+        assert!(!session.graph.is_edge(2, 1));
+    }
+
+    #[test]
+    fn test_queries() {
+        let mut session = create_session();
+        assert_eq!(
+            session.execute("path a b").unwrap(),
+            "step  node  label  distance\n0     0     a      0\n1     2     -      1\n2     1     b      3\n"
+        );
+        assert_eq!(
+            session.execute("neighbors a").unwrap(),
+            "node  name  depth  via\n1     b     1      a\n2     2     1      a\n"
+        );
+        assert_eq!(
+            session.execute("neighbors 2 5").unwrap(),
+            "node  name  depth  via\n1     b     1      2\n"
+        );
+        assert!(session.execute("run bfs a").unwrap().starts_with("node"));
+        assert!(session
+            .execute("edges")
+            .unwrap()
+            .contains("\na     b   4\n"));
+        assert!(session.execute("path a").is_err());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("algo3-repl-test-{}.gml", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut session = create_session();
+        assert!(session.execute("save").is_err());
+        session.execute(&format!("save {path}")).unwrap();
+        assert_eq!(session.path.as_deref(), Some(path));
+        session.execute("remove-node b").unwrap();
+        session.execute("save").unwrap();
+
+        let mut other = Session::new(GraphList::with_nodes(true, 0), None);
+        other.execute(&format!("load {path}")).unwrap();
+        assert_eq!(other.graph.num_nodes(), 2);
+        other.execute("undo").unwrap();
+        assert!(other.graph.undirected);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_run() {
+        let mut session = Session::new(GraphList::with_nodes(false, 0), None);
+        let mut output = vec![];
+        run(
+            &mut session,
+            "add-node x\nbogus\nquit\nadd-node y\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "> Added node 0\n> error: Unknown command: bogus. Type `help` for help.\n> "
+        );
+        assert_eq!(session.graph.num_nodes(), 1);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::components::Components;
+use crate::dfs::dfs_times;
+use crate::graph::GraphList;
+
+// Strongly connected components: nodes that can all reach each other. Both
+// algorithms keep explicit stacks, so deep graphs cannot overflow the call
+// stack, and number the components in order of their lowest node.
+
+// Targets of the edges of every node, in order.
+fn successors(g: &GraphList) -> Vec<Vec<usize>> {
+    g.nodes
+        .iter()
+        .map(|node| node.get_ordered_edge_list().iter().map(|e| e.to).collect())
+        .collect()
+}
+
+/// Tarjan's algorithm: one DFS, where a node whose subtree cannot reach
+/// anything discovered before it is the root of a component.
+pub fn tarjan_scc(g: &GraphList) -> Components {
+    let n = g.num_nodes();
+    let successors = successors(g);
+    let mut index = vec![None; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = vec![];
+    let mut root = vec![0; n];
+    let mut next_index = 0;
+    for start in 0..n {
+        if index[start].is_some() {
+            continue;
+        }
+        // (node, next edge) for every node on the DFS path.
+        let mut path = vec![(start, 0)];
+        index[start] = Some(next_index);
+        low[start] = next_index;
+        next_index += 1;
+        stack.push(start);
+        on_stack[start] = true;
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            if let Some(&to) = successors[node].get(*next) {
+                *next += 1;
+                match index[to] {
+                    None => {
+                        index[to] = Some(next_index);
+                        low[to] = next_index;
+                        next_index += 1;
+                        stack.push(to);
+                        on_stack[to] = true;
+                        path.push((to, 0));
+                    }
+                    Some(i) if on_stack[to] => low[node] = low[node].min(i),
+                    Some(_) => {}
+                }
+                continue;
+            }
+            path.pop();
+            if let Some(&(parent, _)) = path.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+            if Some(low[node]) == index[node] {
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    root[member] = node;
+                    if member == node {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    Components::new(&root)
+}
+
+/// Kosaraju's algorithm: nodes are taken in decreasing finish time of a DFS
+/// on `g`, and each one not yet assigned collects the unassigned nodes that
+/// reach it.
+pub fn kosaraju_scc(g: &GraphList) -> Components {
+    let n = g.num_nodes();
+    let mut predecessors = vec![vec![]; n];
+    for (from, targets) in successors(g).into_iter().enumerate() {
+        for to in targets {
+            predecessors[to].push(from);
+        }
+    }
+    let mut root = vec![None; n];
+    for &start in dfs_times(g).postorder.iter().rev() {
+        if root[start].is_some() {
+            continue;
+        }
+        root[start] = Some(start);
+        let mut pending = vec![start];
+        while let Some(node) = pending.pop() {
+            for &from in &predecessors[node] {
+                if root[from].is_none() {
+                    root[from] = Some(start);
+                    pending.push(from);
+                }
+            }
+        }
+    }
+    let root: Vec<usize> = root.into_iter().map(Option::unwrap).collect();
+    Components::new(&root)
+}
+
+/// The graph of the components: node `c` stands for the nodes in
+/// `components.members[c]`, with an edge to every other component an edge
+/// of `g` leads to, weighing the least of those edges. For strongly
+/// connected components the result is a DAG.
+pub fn condensation(g: &GraphList, components: &Components) -> GraphList {
+    let mut dag = GraphList::with_nodes(false, components.count());
+    for e in g.make_edge_list() {
+        let (from, to) = (components.component[e.from], components.component[e.to]);
+        if from == to {
+            continue;
+        }
+        let weight = match dag.get_edge(from, to).unwrap() {
+            Some(existing) => existing.weight.min(e.weight),
+            None => e.weight,
+        };
+        dag.insert_edge(from, to, weight.0).unwrap();
+    }
+    dag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    // Two cycles, 0 -> 1 -> 2 -> 0 and 3 <-> 4, joined by 2 -> 3 and 1 -> 4,
+    // with 5 depending on 4 and 6 on its own.
+    fn create_test_graph() -> GraphList {
+        let mut graph = GraphList::with_nodes(false, 7);
+        for (from, to, weight) in [
+            (0, 1, 1.0),
+            (1, 2, 1.0),
+            (2, 0, 1.0),
+            (3, 4, 1.0),
+            (4, 3, 1.0),
+            (2, 3, 5.0),
+            (1, 4, 2.0),
+            (5, 4, 1.0),
+        ] {
+            graph.insert_edge(from, to, weight).unwrap();
+        }
+        graph
+    }
+
+    #[test]
+    fn test_scc() {
+        let g = create_test_graph();
+        for components in [tarjan_scc(&g), kosaraju_scc(&g)] {
+            assert_eq!(components.component, vec![0, 0, 0, 1, 1, 2, 3]);
+            assert_eq!(
+                components.members,
+                vec![vec![0, 1, 2], vec![3, 4], vec![5], vec![6]]
+            );
+        }
+        assert_eq!(tarjan_scc(&GraphList::with_nodes(false, 0)).count(), 0);
+    }
+
+    #[test]
+    fn test_undirected_and_self_loops() {
+        let mut g = GraphList::with_nodes(true, 4);
+        g.insert_edge(0, 2, 1.0).unwrap();
+        g.insert_edge(3, 3, 1.0).unwrap();
+        for components in [tarjan_scc(&g), kosaraju_scc(&g)] {
+            assert_eq!(components.component, vec![0, 1, 0, 2]);
+        }
+    }
+
+    #[test]
+    fn test_condensation() {
+        let g = create_test_graph();
+        let components = tarjan_scc(&g);
+        let dag = condensation(&g, &components);
+        assert!(!dag.undirected);
+        assert_eq!(dag.num_nodes(), 4);
+        assert_eq!(dag.make_edge_list().len(), 2);
+        assert_eq!(
+            dag.get_edge(0, 1).unwrap().unwrap().weight,
+            OrderedFloat(2.0)
+        );
+        assert!(dag.is_edge(2, 1));
+        assert_eq!(dag.nodes[3].num_edges(), 0);
+    }
+
+    #[test]
+    fn test_deep_cycle() {
+        let n = 200_000;
+        let mut g = GraphList::with_nodes(false, n);
+        for i in 0..n - 1 {
+            g.insert_edge(i, i + 1, 1.0).unwrap();
+        }
+        assert_eq!(tarjan_scc(&g).count(), n);
+        assert_eq!(kosaraju_scc(&g).count(), n);
+        g.insert_edge(n - 1, 0, 1.0).unwrap();
+        assert_eq!(tarjan_scc(&g).count(), 1);
+        assert_eq!(kosaraju_scc(&g).count(), 1);
+    }
+}
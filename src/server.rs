@@ -0,0 +1,505 @@
+use crate::cli::{distance_value, resolve_node};
+use crate::components::weakly_connected_components;
+use crate::graph::GraphList;
+use crate::paths::{bellman_ford_tree, bfs_tree, dijkstra_tree, ShortestPathTree};
+use ordered_float::OrderedFloat;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::Duration;
+
+// A small HTTP server answering queries on one graph with JSON. Every
+// connection gets a thread and a single request; queries share the graph
+// through a read lock, so only `/reload` waits for them. Slow or oversized
+// requests are cut off, and connections beyond `MAX_CONNECTIONS` are
+// turned away.
+//
+// GET  /info
+// GET  /neighbors?node=<node>
+// GET  /distances?source=<node>
+// GET  /shortest-path?source=<node>&target=<node>
+// GET  /reachable?source=<node>[&target=<node>]
+// GET  /components
+// POST /reload                     reads the served file again
+//
+// Parameters can also be given as a JSON object in the request body. Nodes
+// are given by label or index and returned as {"index": i, "label": ...}.
+
+/// Largest request body accepted.
+const MAX_BODY: usize = 1 << 20;
+/// Largest request line and headers accepted, together.
+const MAX_HEAD: u64 = 16 << 10;
+/// How long a client may take to send its request.
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Most connections answered at once.
+const MAX_CONNECTIONS: usize = 64;
+
+pub struct ServerState {
+    pub graph: GraphList,
+    /// File reloaded by `/reload`.
+    pub path: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub params: HashMap<String, String>,
+}
+
+// Decodes `%XX` escapes and `+` in a query string component.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => match text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    out.push(byte);
+                    i += 2;
+                }
+                None => out.push(b'%'),
+            },
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Reads one HTTP request: the request line, the headers and a body of
+/// `Content-Length` bytes, which when present must be a JSON object.
+pub fn read_request(reader: &mut impl BufRead) -> Result<Request, String> {
+    let mut head = io::Read::take(&mut *reader, MAX_HEAD);
+    let mut read_line = |line: &mut String| {
+        let read = head.read_line(line).map_err(|e| e.to_string())?;
+        if head.limit() == 0 {
+            return Err("Request headers too large".to_string());
+        }
+        Ok(read)
+    };
+    let mut line = String::new();
+    read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err("Malformed request line".to_string());
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        params: query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(key), percent_decode(value))
+            })
+            .collect(),
+    };
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if read_line(&mut header)? == 0 {
+            break;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| "Invalid Content-Length".to_string())?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err("Request body too large".to_string());
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+    if body.iter().any(|b| !b.is_ascii_whitespace()) {
+        let body: Value =
+            serde_json::from_slice(&body).map_err(|e| format!("Invalid JSON body: {e}"))?;
+        let Value::Object(fields) = body else {
+            return Err("The JSON body must be an object".to_string());
+        };
+        for (key, value) in fields {
+            let value = match value {
+                Value::String(text) => text,
+                other => other.to_string(),
+            };
+            request.params.insert(key, value);
+        }
+    }
+    Ok(request)
+}
+
+fn node_json(g: &GraphList, i: usize) -> Value {
+    json!({ "index": i, "label": g.nodes[i].label() })
+}
+
+// Distances from `source` and their tree, by Bellman-Ford when there are
+// negative weights.
+fn distances(
+    g: &GraphList,
+    source: usize,
+) -> Result<(Vec<OrderedFloat<f64>>, ShortestPathTree), String> {
+    if g.make_edge_list()
+        .iter()
+        .any(|e| e.weight < OrderedFloat(0.0))
+    {
+        bellman_ford_tree(g, source).ok_or("Negative cycle reachable from the source".to_string())
+    } else {
+        Ok(dijkstra_tree(g, source))
+    }
+}
+
+fn query(g: &GraphList, request: &Request) -> Result<Value, (u16, String)> {
+    let bad = |error: String| (400, error);
+    let node = |name: &str| -> Result<usize, (u16, String)> {
+        let value = request
+            .params
+            .get(name)
+            .ok_or_else(|| bad(format!("Missing parameter `{name}`")))?;
+        resolve_node(g, value).map_err(bad)
+    };
+    match request.path.as_str() {
+        "/" | "/info" => Ok(json!({
+            "nodes": g.num_nodes(),
+            "edges": g.num_edges(),
+            "undirected": g.undirected,
+        })),
+        "/neighbors" => {
+            let i = node("node")?;
+            let out: Vec<Value> = g.nodes[i]
+                .get_ordered_edge_list()
+                .iter()
+                .map(|e| json!({ "node": node_json(g, e.to), "weight": e.weight.0 }))
+                .collect();
+            let mut result = json!({ "node": node_json(g, i), "out": out });
+            if !g.undirected {
+                let mut incoming: Vec<_> = g
+                    .make_edge_list()
+                    .into_iter()
+                    .filter(|e| e.to == i)
+                    .collect();
+                incoming.sort_by_key(|e| e.from);
+                result["in"] = incoming
+                    .iter()
+                    .map(|e| json!({ "node": node_json(g, e.from), "weight": e.weight.0 }))
+                    .collect();
+            }
+            Ok(result)
+        }
+        "/distances" => {
+            let source = node("source")?;
+            let (distance, _) = distances(g, source).map_err(|e| (422, e))?;
+            Ok(json!({
+                "source": node_json(g, source),
+                "distances": distance.iter().map(|&d| distance_value(d)).collect::<Vec<_>>(),
+            }))
+        }
+        "/shortest-path" => {
+            let (source, target) = (node("source")?, node("target")?);
+            let (distance, tree) = distances(g, source).map_err(|e| (422, e))?;
+            let path = tree
+                .path_to(target)
+                .map(|path| path.iter().map(|&i| node_json(g, i)).collect::<Vec<_>>());
+            Ok(json!({
+                "source": node_json(g, source),
+                "target": node_json(g, target),
+                "distance": distance_value(distance[target]),
+                "path": path,
+            }))
+        }
+        "/reachable" => {
+            let source = node("source")?;
+            let tree = bfs_tree(g, source);
+            if request.params.contains_key("target") {
+                let target = node("target")?;
+                Ok(json!({ "reachable": tree.reaches(target) }))
+            } else {
+                let nodes: Vec<Value> = (0..g.num_nodes())
+                    .filter(|&i| tree.reaches(i))
+                    .map(|i| node_json(g, i))
+                    .collect();
+                Ok(json!({ "reachable": nodes }))
+            }
+        }
+        "/components" => {
+            let components = weakly_connected_components(g);
+            Ok(json!({
+                "count": components.count(),
+                "sizes": components.sizes(),
+                "component": components.component,
+            }))
+        }
+        path => Err((404, format!("No endpoint {path}"))),
+    }
+}
+
+// Reads the served file again. Clients cannot name another file, as that
+// would let them read any file the server can.
+fn reload(state: &RwLock<ServerState>, request: &Request) -> Result<Value, (u16, String)> {
+    if request.params.contains_key("path") {
+        return Err((403, "Only the served file can be reloaded".to_string()));
+    }
+    let path = state
+        .read()
+        .unwrap()
+        .path
+        .clone()
+        .ok_or((400, "No file to reload".to_string()))?;
+    // Loading happens outside the lock, so queries go on meanwhile.
+    let (graph, format) = GraphList::load(&path).map_err(|e| (422, e))?;
+    let result = json!({
+        "path": path,
+        "format": format.to_string(),
+        "nodes": graph.num_nodes(),
+        "edges": graph.num_edges(),
+    });
+    state.write().unwrap().graph = graph;
+    Ok(result)
+}
+
+/// Answers a request with a status code and a JSON body.
+pub fn handle(state: &RwLock<ServerState>, request: &Request) -> (u16, Value) {
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/reload") => reload(state, request),
+        (_, "/reload") => Err((405, "Use POST for /reload".to_string())),
+        ("GET" | "POST", _) => query(&state.read().unwrap().graph, request),
+        (method, _) => Err((405, format!("Method {method} not allowed"))),
+    };
+    match result {
+        Ok(body) => (200, body),
+        Err((status, error)) => (status, json!({ "error": error })),
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        422 => "Unprocessable Entity",
+        503 => "Service Unavailable",
+        _ => "Error",
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: u16, body: &Value) -> io::Result<()> {
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        reason(status),
+        body.len()
+    )?;
+    stream.flush()
+}
+
+fn handle_connection(state: &RwLock<ServerState>, mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let request = read_request(&mut BufReader::new(&mut stream));
+    let (status, body) = match request {
+        Ok(request) => handle(state, &request),
+        Err(error) => (400, json!({ "error": error })),
+    };
+    respond(&mut stream, status, &body)
+}
+
+/// Answers connections on `listener` until it fails.
+pub fn serve(listener: TcpListener, state: Arc<RwLock<ServerState>>) -> io::Result<()> {
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        // A client hanging up early is not the server's problem.
+        if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            active.fetch_sub(1, Ordering::SeqCst);
+            let _ = stream.set_write_timeout(Some(TIMEOUT));
+            let _ = respond(
+                &mut stream,
+                503,
+                &json!({ "error": "Too many connections" }),
+            );
+            continue;
+        }
+        let (state, active) = (Arc::clone(&state), Arc::clone(&active));
+        thread::spawn(move || {
+            let _ = handle_connection(&state, stream);
+            active.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn create_state() -> RwLock<ServerState> {
+        let mut graph = GraphList::with_nodes(false, 0);
+        graph.insert_node(Some("a".to_string()));
+        graph.insert_node(Some("b c".to_string()));
+        graph.insert_node(None);
+        graph.insert_node(None);
+        graph.insert_edge(0, 1, 4.0).unwrap();
+        graph.insert_edge(0, 2, 1.0).unwrap();
+        graph.insert_edge(2, 1, 2.0).unwrap();
+        RwLock::new(ServerState { graph, path: None })
+    }
+
+    fn get(state: &RwLock<ServerState>, target: &str) -> (u16, Value) {
+        let text = format!("GET {target} HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        handle(state, &read_request(&mut text.as_bytes()).unwrap())
+    }
+
+    #[test]
+    fn test_read_request() {
+        let text = "POST /shortest-path?source=b%20c&x=1+2 HTTP/1.1\r\nContent-Length: 14\r\n\r\n{\"target\": 3}\n";
+        let request = read_request(&mut text.as_bytes()).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/shortest-path");
+        assert_eq!(request.params["source"], "b c");
+        assert_eq!(request.params["x"], "1 2");
+        assert_eq!(request.params["target"], "3");
+        let text = "POST /info HTTP/1.1\r\nContent-Length: 2\r\n\r\n[]";
+        assert!(read_request(&mut text.as_bytes()).is_err());
+        assert!(read_request(&mut "\r\n".as_bytes()).is_err());
+        // Endless request lines and headers are cut off.
+        let endless =
+            |start: &'static str| BufReader::new(start.as_bytes().chain(io::repeat(b'a')));
+        assert_eq!(
+            read_request(&mut endless("GET /")).unwrap_err(),
+            "Request headers too large"
+        );
+        assert_eq!(
+            read_request(&mut endless("GET / HTTP/1.1\r\nX-Long: ")).unwrap_err(),
+            "Request headers too large"
+        );
+    }
+
+    #[test]
+    fn test_queries() {
+        let state = create_state();
+        assert_eq!(
+            get(&state, "/info"),
+            (200, json!({ "nodes": 4, "edges": 3, "undirected": false }))
+        );
+        let (status, body) = get(&state, "/shortest-path?source=a&target=b+c");
+        assert_eq!(status, 200);
+        assert_eq!(body["distance"], json!(3.0));
+        assert_eq!(
+            body["path"],
+            json!([
+                { "index": 0, "label": "a" },
+                { "index": 2, "label": null },
+                { "index": 1, "label": "b c" },
+            ])
+        );
+        let (_, body) = get(&state, "/shortest-path?source=a&target=3");
+        assert_eq!(body["path"], Value::Null);
+        let (_, body) = get(&state, "/distances?source=2");
+        assert_eq!(body["distances"], json!([null, 2.0, 0.0, null]));
+        assert_eq!(
+            get(&state, "/reachable?source=2&target=a").1,
+            json!({ "reachable": false })
+        );
+        let (_, body) = get(&state, "/reachable?source=2");
+        assert_eq!(body["reachable"].as_array().unwrap().len(), 2);
+        let (_, body) = get(&state, "/neighbors?node=1");
+        assert_eq!(body["out"], json!([]));
+        assert_eq!(body["in"].as_array().unwrap().len(), 2);
+        let (_, body) = get(&state, "/components");
+        assert_eq!(body["component"], json!([0, 0, 0, 1]));
+        assert_eq!(body["sizes"], json!([3, 1]));
+    }
+
+    #[test]
+    fn test_errors() {
+        let state = create_state();
+        assert_eq!(get(&state, "/nowhere").0, 404);
+        assert_eq!(get(&state, "/distances").0, 400);
+        assert_eq!(get(&state, "/distances?source=z").0, 400);
+        assert_eq!(get(&state, "/reload").0, 405);
+        state
+            .write()
+            .unwrap()
+            .graph
+            .insert_edge(1, 0, -9.0)
+            .unwrap();
+        assert_eq!(get(&state, "/distances?source=a").0, 422);
+        let request = Request {
+            method: "POST".to_string(),
+            path: "/reload".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(handle(&state, &request).0, 400);
+        let mut request = request;
+        request
+            .params
+            .insert("path".to_string(), "/etc/passwd".to_string());
+        assert_eq!(handle(&state, &request).0, 403);
+    }
+
+    #[test]
+    fn test_serve() {
+        let path =
+            std::env::temp_dir().join(format!("algo3-server-test-{}.gml", std::process::id()));
+        std::fs::write(
+            &path,
+            "graph [ node [ id 0 ] node [ id 1 ] edge [ source 0 target 1 ] ]",
+        )
+        .unwrap();
+        let mut state = create_state().into_inner().unwrap();
+        state.path = Some(path.to_string_lossy().into_owned());
+        let state = Arc::new(RwLock::new(state));
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn({
+            let state = Arc::clone(&state);
+            move || serve(listener, state)
+        });
+        let send = move |request: String| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let clients: Vec<_> = (0..4)
+            .map(|_| thread::spawn(move || send("GET /info HTTP/1.1\r\n\r\n".to_string())))
+            .collect();
+        for client in clients {
+            let response = client.join().unwrap();
+            assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+            assert!(response.ends_with("{\"edges\":3,\"nodes\":4,\"undirected\":false}"));
+        }
+
+        let response = send("POST /reload HTTP/1.1\r\n\r\n".to_string());
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert_eq!(state.read().unwrap().graph.num_nodes(), 2);
+        let response = send("GET /missing HTTP/1.1\r\n\r\n".to_string());
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+use crate::dfs::dfs_connected_componentes;
+use crate::graph::GraphList;
+use crate::topological::kahn_topological_sort;
+use ordered_float::OrderedFloat;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DegreeStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    /// Number of nodes with each degree.
+    pub histogram: BTreeMap<usize, usize>,
+}
+
+impl DegreeStats {
+    fn new(degrees: &[usize]) -> Self {
+        let mut histogram = BTreeMap::new();
+        for &d in degrees {
+            *histogram.entry(d).or_insert(0) += 1;
+        }
+        DegreeStats {
+            min: degrees.iter().copied().min().unwrap_or(0),
+            max: degrees.iter().copied().max().unwrap_or(0),
+            mean: if degrees.is_empty() {
+                0.0
+            } else {
+                degrees.iter().sum::<usize>() as f64 / degrees.len() as f64
+            },
+            histogram,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphStats {
+    pub nodes: usize,
+    /// Undirected edges count once.
+    pub edges: usize,
+    pub undirected: bool,
+    /// Fraction of the possible edges between distinct nodes that exist.
+    /// Self-loops are left out.
+    pub density: f64,
+    /// Out-degrees for directed graphs. A self-loop adds 1.
+    pub degrees: DegreeStats,
+    /// Only for directed graphs.
+    pub in_degrees: Option<DegreeStats>,
+    /// Weakly connected ones for directed graphs.
+    pub components: usize,
+    pub self_loops: usize,
+    pub negative_edges: usize,
+    /// Undirected graphs are DAGs only when they have no edges.
+    pub is_dag: bool,
+}
+
+pub fn stats(g: &GraphList) -> GraphStats {
+    let n = g.num_nodes();
+    let all = g.make_edge_list();
+    let edges = g.make_unique_edge_list();
+    let self_loops = edges.iter().filter(|e| e.from == e.to).count();
+    let pairs = if g.undirected {
+        n * n.saturating_sub(1) / 2
+    } else {
+        n * n.saturating_sub(1)
+    };
+    let out_degrees: Vec<usize> = g.nodes.iter().map(|node| node.num_edges()).collect();
+    let in_degrees = (!g.undirected).then(|| {
+        let mut in_degrees = vec![0; n];
+        for e in &all {
+            in_degrees[e.to] += 1;
+        }
+        DegreeStats::new(&in_degrees)
+    });
+    let component = dfs_connected_componentes(g);
+    GraphStats {
+        nodes: n,
+        edges: edges.len(),
+        undirected: g.undirected,
+        density: if pairs == 0 {
+            0.0
+        } else {
+            (edges.len() - self_loops) as f64 / pairs as f64
+        },
+        degrees: DegreeStats::new(&out_degrees),
+        in_degrees,
+        components: component.iter().max().map_or(0, |&c| c as usize + 1),
+        self_loops,
+        negative_edges: edges
+            .iter()
+            .filter(|e| e.weight < OrderedFloat(0.0))
+            .count(),
+        is_dag: kahn_topological_sort(g).is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directed_stats() {
+        let mut g = GraphList::with_nodes(false, 4);
+        g.insert_edge(0, 1, 1.0).unwrap();
+        g.insert_edge(0, 2, -2.0).unwrap();
+        g.insert_edge(1, 2, 1.0).unwrap();
+        let s = stats(&g);
+        assert_eq!(s.nodes, 4);
+        assert_eq!(s.edges, 3);
+        assert!(!s.undirected);
+        assert_eq!(s.density, 3.0 / 12.0);
+        assert_eq!(s.degrees.min, 0);
+        assert_eq!(s.degrees.max, 2);
+        assert_eq!(s.degrees.mean, 0.75);
+        assert_eq!(
+            s.degrees.histogram,
+            BTreeMap::from([(0, 2), (1, 1), (2, 1)])
+        );
+        let in_degrees = s.in_degrees.unwrap();
+        assert_eq!(in_degrees.max, 2);
+        assert_eq!(
+            in_degrees.histogram,
+            BTreeMap::from([(0, 2), (1, 1), (2, 1)])
+        );
+        assert_eq!(s.components, 2);
+        assert_eq!(s.self_loops, 0);
+        assert_eq!(s.negative_edges, 1);
+        assert!(s.is_dag);
+
+        g.insert_edge(2, 0, 1.0).unwrap();
+        assert!(!stats(&g).is_dag);
+        g.remove_edge(2, 0).unwrap();
+        g.insert_edge(3, 3, 1.0).unwrap();
+        let s = stats(&g);
+        assert!(!s.is_dag);
+        assert_eq!(s.self_loops, 1);
+        assert_eq!(s.density, 3.0 / 12.0);
+    }
+
+    #[test]
+    fn test_undirected_stats() {
+        let mut g = GraphList::with_nodes(true, 3);
+        assert!(stats(&g).is_dag);
+        g.insert_edge(0, 1, 1.0).unwrap();
+        g.insert_edge(1, 1, 1.0).unwrap();
+        let s = stats(&g);
+        assert_eq!(s.edges, 2);
+        assert_eq!(s.density, 1.0 / 3.0);
+        assert_eq!(
+            s.degrees.histogram,
+            BTreeMap::from([(0, 1), (1, 1), (2, 1)])
+        );
+        assert!(s.in_degrees.is_none());
+        assert_eq!(s.components, 2);
+        assert!(!s.is_dag);
+    }
+
+    #[test]
+    fn test_empty_graph() {
+        let s = stats(&GraphList::with_nodes(false, 0));
+        assert_eq!(s.nodes, 0);
+        assert_eq!(s.density, 0.0);
+        assert_eq!(s.degrees, DegreeStats::default());
+        assert_eq!(s.components, 0);
+        assert!(s.is_dag);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::dfs::{dfs_times, EdgeKind};
+use crate::graph::GraphList;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+// Orders of the nodes where every edge goes forward. Undirected edges count
+// as arcs both ways, so only graphs without edges have one.
+
+fn in_degrees(g: &GraphList) -> Vec<usize> {
+    let mut in_degree = vec![0; g.num_nodes()];
+    for e in g.make_edge_list() {
+        in_degree[e.to] += 1;
+    }
+    in_degree
+}
+
+/// Kahn's algorithm: repeatedly takes out a node without remaining
+/// predecessors, first in first out. None if the graph has a cycle.
+pub fn kahn_topological_sort(g: &GraphList) -> Option<Vec<usize>> {
+    let mut in_degree = in_degrees(g);
+    let mut pending: VecDeque<usize> = (0..g.num_nodes()).filter(|&i| in_degree[i] == 0).collect();
+    let mut order = Vec::with_capacity(g.num_nodes());
+    while let Some(node) = pending.pop_front() {
+        order.push(node);
+        for e in g.nodes[node].get_ordered_edge_list() {
+            in_degree[e.to] -= 1;
+            if in_degree[e.to] == 0 {
+                pending.push_back(e.to);
+            }
+        }
+    }
+    (order.len() == g.num_nodes()).then_some(order)
+}
+
+/// Like `kahn_topological_sort`, always taking out the lowest node, which
+/// gives the lexicographically smallest order.
+pub fn lexicographic_topological_sort(g: &GraphList) -> Option<Vec<usize>> {
+    let mut in_degree = in_degrees(g);
+    let mut pending: BinaryHeap<Reverse<usize>> = (0..g.num_nodes())
+        .filter(|&i| in_degree[i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(g.num_nodes());
+    while let Some(Reverse(node)) = pending.pop() {
+        order.push(node);
+        for e in g.nodes[node].get_edge_list() {
+            in_degree[e.to] -= 1;
+            if in_degree[e.to] == 0 {
+                pending.push(Reverse(e.to));
+            }
+        }
+    }
+    (order.len() == g.num_nodes()).then_some(order)
+}
+
+/// Nodes in decreasing DFS finish time. None if the DFS finds a back edge,
+/// that is a cycle.
+pub fn dfs_topological_sort(g: &GraphList) -> Option<Vec<usize>> {
+    let times = dfs_times(g);
+    if times.edges.values().any(|&kind| kind == EdgeKind::Back) {
+        return None;
+    }
+    let mut order = times.postorder;
+    order.reverse();
+    Some(order)
+}
+
+/// A topological order, or a cycle proving there is none.
+pub fn topological_sort(g: &GraphList) -> Result<Vec<usize>, Vec<usize>> {
+    kahn_topological_sort(g).ok_or_else(|| find_cycle(g).expect("Kahn's algorithm found a cycle"))
+}
+
+/// The nodes of a cycle in order, each with an edge to the next and the
+/// last to the first, or None if the graph is acyclic. A self-loop is a
+/// cycle of one node.
+pub fn find_cycle(g: &GraphList) -> Option<Vec<usize>> {
+    let times = dfs_times(g);
+    // The lowest back edge, so the result does not depend on hash order.
+    let &(from, to) = times
+        .edges
+        .iter()
+        .filter(|(_, &kind)| kind == EdgeKind::Back)
+        .map(|(edge, _)| edge)
+        .min()?;
+    // A back edge leads to an ancestor, so tree edges lead back to `from`.
+    let mut cycle = vec![from];
+    let mut node = from;
+    while node != to {
+        node = times.parent[node].unwrap();
+        cycle.push(node);
+    }
+    cycle.reverse();
+    Some(cycle)
+}
+
+/// Every topological order in lexicographic order, stopping after `limit`
+/// of them, as there can be up to n! of them. Empty if `g` has a cycle.
+pub fn all_topological_sorts(g: &GraphList, limit: usize) -> Vec<Vec<usize>> {
+    fn extend(
+        g: &GraphList,
+        in_degree: &mut [usize],
+        order: &mut Vec<usize>,
+        placed: &mut [bool],
+        orders: &mut Vec<Vec<usize>>,
+        limit: usize,
+    ) {
+        if order.len() == g.num_nodes() {
+            orders.push(order.clone());
+            return;
+        }
+        for node in 0..g.num_nodes() {
+            if orders.len() == limit {
+                return;
+            }
+            if placed[node] || in_degree[node] > 0 {
+                continue;
+            }
+            placed[node] = true;
+            order.push(node);
+            for e in g.nodes[node].get_edge_list() {
+                in_degree[e.to] -= 1;
+            }
+            extend(g, in_degree, order, placed, orders, limit);
+            for e in g.nodes[node].get_edge_list() {
+                in_degree[e.to] += 1;
+            }
+            order.pop();
+            placed[node] = false;
+        }
+    }
+    let mut orders = vec![];
+    // Without this check the search would try every order of the nodes
+    // before the cycle, whatever the limit.
+    if limit > 0 && kahn_topological_sort(g).is_some() {
+        extend(
+            g,
+            &mut in_degrees(g),
+            &mut vec![],
+            &mut vec![false; g.num_nodes()],
+            &mut orders,
+            limit,
+        );
+    }
+    orders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 5 -> 2 -> 3 -> 1, 5 -> 0, 4 -> 0, 4 -> 1
+    fn create_test_graph() -> GraphList {
+        let mut graph = GraphList::with_nodes(false, 6);
+        for (from, to) in [(5, 2), (5, 0), (4, 0), (4, 1), (2, 3), (3, 1)] {
+            graph.insert_edge(from, to, 1.0).unwrap();
+        }
+        graph
+    }
+
+    fn is_topological(g: &GraphList, order: &[usize]) -> bool {
+        let mut position = vec![0; g.num_nodes()];
+        for (i, &node) in order.iter().enumerate() {
+            position[node] = i;
+        }
+        order.len() == g.num_nodes()
+            && g.make_edge_list()
+                .iter()
+                .all(|e| position[e.from] < position[e.to])
+    }
+
+    #[test]
+    fn test_topological_sorts() {
+        let g = create_test_graph();
+        assert_eq!(kahn_topological_sort(&g), Some(vec![4, 5, 0, 2, 3, 1]));
+        assert_eq!(
+            lexicographic_topological_sort(&g),
+            Some(vec![4, 5, 0, 2, 3, 1])
+        );
+        let order = dfs_topological_sort(&g).unwrap();
+        assert_eq!(order, vec![5, 4, 2, 3, 1, 0]);
+        assert!(is_topological(&g, &order));
+        assert_eq!(topological_sort(&g), Ok(vec![4, 5, 0, 2, 3, 1]));
+        assert_eq!(find_cycle(&g), None);
+    }
+
+    #[test]
+    fn test_lexicographic_order() {
+        // Kahn's queue takes 3 before 1, which only becomes free later.
+        let mut g = GraphList::with_nodes(false, 4);
+        g.insert_edge(2, 1, 1.0).unwrap();
+        g.insert_edge(0, 3, 1.0).unwrap();
+        assert_eq!(kahn_topological_sort(&g), Some(vec![0, 2, 3, 1]));
+        assert_eq!(lexicographic_topological_sort(&g), Some(vec![0, 2, 1, 3]));
+    }
+
+    #[test]
+    fn test_cycles() {
+        let mut g = create_test_graph();
+        g.insert_edge(1, 5, 1.0).unwrap();
+        assert_eq!(kahn_topological_sort(&g), None);
+        assert_eq!(lexicographic_topological_sort(&g), None);
+        assert_eq!(dfs_topological_sort(&g), None);
+        assert_eq!(find_cycle(&g), Some(vec![1, 5, 2, 3]));
+        assert_eq!(topological_sort(&g), Err(vec![1, 5, 2, 3]));
+        assert!(all_topological_sorts(&g, 10).is_empty());
+
+        // A cycle next to many free nodes must not be searched exhaustively.
+        let mut g = GraphList::with_nodes(false, 14);
+        g.insert_edge(12, 13, 1.0).unwrap();
+        g.insert_edge(13, 12, 1.0).unwrap();
+        assert!(all_topological_sorts(&g, 1).is_empty());
+
+        let mut g = GraphList::with_nodes(false, 2);
+        g.insert_edge(1, 1, 1.0).unwrap();
+        assert_eq!(find_cycle(&g), Some(vec![1]));
+
+        let mut g = GraphList::with_nodes(true, 2);
+        assert_eq!(topological_sort(&g), Ok(vec![0, 1]));
+        g.insert_edge(0, 1, 1.0).unwrap();
+        assert_eq!(find_cycle(&g), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn test_all_topological_sorts() {
+        let g = create_test_graph();
+        let orders = all_topological_sorts(&g, usize::MAX);
+        assert_eq!(orders.len(), 13);
+        assert_eq!(orders[0], lexicographic_topological_sort(&g).unwrap());
+        assert!(orders.iter().all(|order| is_topological(&g, order)));
+        assert!(orders.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(all_topological_sorts(&g, 3), orders[..3]);
+        assert!(all_topological_sorts(&g, 0).is_empty());
+        assert_eq!(
+            all_topological_sorts(&GraphList::with_nodes(false, 3), 100).len(),
+            6
+        );
+        assert_eq!(
+            all_topological_sorts(&GraphList::with_nodes(false, 0), 100),
+            vec![Vec::<usize>::new()]
+        );
+    }
+}
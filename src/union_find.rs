@@ -0,0 +1,120 @@
+// Disjoint sets of the elements 0..n, merged by size with path compression,
+// so a sequence of operations takes nearly constant time each.
+
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    num_sets: usize,
+}
+
+impl UnionFind {
+    /// `n` elements, each in a set of its own.
+    pub fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            num_sets: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn num_sets(&self) -> usize {
+        self.num_sets
+    }
+
+    /// Adds an element in a set of its own and returns it.
+    pub fn add(&mut self) -> usize {
+        self.parent.push(self.len());
+        self.size.push(1);
+        self.num_sets += 1;
+        self.len() - 1
+    }
+
+    /// The representative of the set of `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            x = std::mem::replace(&mut self.parent[x], root);
+        }
+        root
+    }
+
+    /// Merges the sets of `a` and `b`, returning false if they already were
+    /// one.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.num_sets -= 1;
+        true
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn set_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert_eq!(sets.num_sets(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert_eq!(sets.num_sets(), 3);
+        assert!(sets.same_set(0, 3));
+        assert!(!sets.same_set(0, 4));
+        assert_eq!(sets.set_size(2), 4);
+        assert_eq!(sets.set_size(5), 1);
+
+        let x = sets.add();
+        assert_eq!(x, 6);
+        assert_eq!(sets.len(), 7);
+        assert!(sets.union(x, 5));
+        assert_eq!(sets.num_sets(), 3);
+        assert_eq!(sets.set_size(5), 2);
+    }
+
+    #[test]
+    fn test_path_compression() {
+        let n = 100_000;
+        let mut sets = UnionFind::new(n);
+        for i in 1..n {
+            sets.union(i, i - 1);
+        }
+        let root = sets.find(0);
+        for i in 0..n {
+            assert_eq!(sets.find(i), root);
+        }
+        assert_eq!(sets.set_size(n - 1), n);
+        assert!(UnionFind::new(0).is_empty());
+    }
+}
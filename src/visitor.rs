@@ -0,0 +1,27 @@
+use crate::graph::Edge;
+
+// Events of a graph traversal, reported by `bfs_visit` and `dfs_visit`.
+// Implement the methods of interest; the others do nothing.
+//
+// Every edge leaving a discovered node is examined once, then reported as
+// exactly one of tree, back, forward or cross edge. Undirected edges are
+// stored both ways, so they are examined from both ends.
+
+pub trait Visitor {
+    /// `node` is reached for the first time.
+    fn discover(&mut self, _node: usize) {}
+    /// An edge leaving a discovered node is looked at.
+    fn examine_edge(&mut self, _edge: &Edge) {}
+    /// The edge discovers its target.
+    fn tree_edge(&mut self, _edge: &Edge) {}
+    /// DFS only: the edge leads to an ancestor still being explored.
+    fn back_edge(&mut self, _edge: &Edge) {}
+    /// DFS only: the edge leads to a finished descendant.
+    fn forward_edge(&mut self, _edge: &Edge) {}
+    /// Any other edge to an already discovered node. BFS reports all its
+    /// non-tree edges this way.
+    fn cross_edge(&mut self, _edge: &Edge) {}
+    /// All edges of `node` have been examined, and in DFS all of its
+    /// descendants finished.
+    fn finish(&mut self, _node: usize) {}
+}